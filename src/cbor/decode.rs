@@ -0,0 +1,332 @@
+use crate::error::Error;
+use crate::types::{canonical_key_order, Bytes, DocumentValue};
+
+use super::{
+    encode::{f32_to_f16, CANONICAL_NAN},
+    MAJOR_ARRAY, MAJOR_BYTES, MAJOR_MAP, MAJOR_NEGATIVE, MAJOR_TAG, MAJOR_TEXT, MAJOR_UNSIGNED,
+    SIMPLE_FALSE, SIMPLE_FLOAT_16, SIMPLE_FLOAT_32, SIMPLE_FLOAT_64, SIMPLE_NULL, SIMPLE_TRUE,
+    SIMPLE_UNDEFINED, TAG_NEGATIVE_BIGNUM, TAG_POSITIVE_BIGNUM,
+};
+
+type Result<K> = std::result::Result<K, Error>;
+
+const INDEFINITE: u8 = 31;
+const BREAK: u8 = 0xff;
+const MAX_DEPTH: usize = 256;
+
+/// Decodes CBOR into [`DocumentValue`]. With the canonical check enabled, any input which
+/// isn't in the canonical form produced by [`super::to_canonical_cbor`] is rejected
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+    canonical: bool,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            canonical: false,
+        }
+    }
+
+    pub fn with_canonical_check(mut self, canonical: bool) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// Decodes a single value. Trailing bytes are an error
+    pub fn decode(mut self) -> Result<DocumentValue> {
+        let value = self.decode_value(0)?;
+        if self.position != self.data.len() {
+            return Err(Error::cbor(format!(
+                "{} trailing bytes after the value",
+                self.data.len() - self.position
+            )));
+        }
+        Ok(value)
+    }
+
+    fn decode_value(&mut self, depth: usize) -> Result<DocumentValue> {
+        if depth > MAX_DEPTH {
+            return Err(Error::cbor("maximum nesting depth exceeded"));
+        }
+
+        let initial = self.read_u8()?;
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        match major {
+            MAJOR_UNSIGNED => Ok(DocumentValue::UInteger(self.read_argument(info)?)),
+            MAJOR_NEGATIVE => {
+                let n = self.read_argument(info)?;
+                Ok(DocumentValue::from_i128(-1 - n as i128))
+            }
+            MAJOR_BYTES => Ok(DocumentValue::Bytes(Bytes(self.read_string(major, info)?))),
+            MAJOR_TEXT => Ok(DocumentValue::String(self.read_text(info)?)),
+            MAJOR_ARRAY => self.decode_array(info, depth),
+            MAJOR_MAP => self.decode_map(info, depth),
+            MAJOR_TAG => self.decode_tagged(info, depth),
+            // major type 7
+            _ => self.decode_simple(info),
+        }
+    }
+
+    fn decode_array(&mut self, info: u8, depth: usize) -> Result<DocumentValue> {
+        let mut array = Vec::new();
+        if info == INDEFINITE {
+            self.check_definite()?;
+            while !self.consume_break()? {
+                array.push(self.decode_value(depth + 1)?);
+            }
+        } else {
+            let len = self.read_length(info)?;
+            array.reserve(len.min(self.remaining()));
+            for _ in 0..len {
+                array.push(self.decode_value(depth + 1)?);
+            }
+        }
+        Ok(DocumentValue::Array(array))
+    }
+
+    fn decode_map(&mut self, info: u8, depth: usize) -> Result<DocumentValue> {
+        let mut entries = Vec::new();
+        let mut previous_key: Option<String> = None;
+
+        let len = if info == INDEFINITE {
+            self.check_definite()?;
+            None
+        } else {
+            Some(self.read_length(info)?)
+        };
+
+        let mut count = 0;
+        loop {
+            match len {
+                Some(len) if count == len => break,
+                None if self.consume_break()? => break,
+                _ => {}
+            }
+
+            let key = self.decode_key()?;
+            if self.canonical {
+                if let Some(previous) = &previous_key {
+                    if canonical_key_order(previous, &key).is_gt() {
+                        return Err(Error::non_canonical_cbor(format!(
+                            "map key '{key}' is out of order"
+                        )));
+                    }
+                }
+            }
+            let value = self.decode_value(depth + 1)?;
+            if self.canonical {
+                previous_key = Some(key.clone());
+            }
+            entries.push((key, value));
+            count += 1;
+        }
+
+        entries.sort_by(|(a, _), (b, _)| canonical_key_order(a, b));
+        if let Some(pair) = entries.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(Error::cbor(format!("duplicate map key '{}'", pair[0].0)));
+        }
+        Ok(DocumentValue::Map(entries.into_iter().collect()))
+    }
+
+    fn decode_key(&mut self) -> Result<String> {
+        let initial = self.read_u8()?;
+        if initial >> 5 != MAJOR_TEXT {
+            return Err(Error::cbor("map keys must be text strings"));
+        }
+        self.read_text(initial & 0x1f)
+    }
+
+    fn decode_tagged(&mut self, info: u8, depth: usize) -> Result<DocumentValue> {
+        let tag = self.read_argument(info)?;
+        if tag != TAG_POSITIVE_BIGNUM && tag != TAG_NEGATIVE_BIGNUM {
+            if self.canonical {
+                return Err(Error::non_canonical_cbor(format!("unsupported tag {tag}")));
+            }
+            return self.decode_value(depth + 1);
+        }
+
+        let initial = self.read_u8()?;
+        if initial >> 5 != MAJOR_BYTES {
+            return Err(Error::cbor("bignum must be a byte string"));
+        }
+        let bytes = self.read_string(MAJOR_BYTES, initial & 0x1f)?;
+        let significant = match bytes.iter().position(|b| *b != 0) {
+            Some(first) => &bytes[first..],
+            None => &[],
+        };
+        if significant.len() > 16 {
+            return Err(Error::cbor("bignum exceeds 128 bits"));
+        }
+        if self.canonical && (significant.len() != bytes.len() || significant.len() <= 8) {
+            return Err(Error::non_canonical_cbor(
+                "bignum has leading zeros or fits into 64 bits",
+            ));
+        }
+
+        let mut buffer = [0_u8; 16];
+        buffer[16 - significant.len()..].copy_from_slice(significant);
+        let n = u128::from_be_bytes(buffer);
+
+        if tag == TAG_POSITIVE_BIGNUM {
+            return Ok(DocumentValue::from_u128(n));
+        }
+        match i128::try_from(n) {
+            Ok(n) => Ok(DocumentValue::from_i128(-1 - n)),
+            Err(_) => Err(Error::cbor("negative bignum exceeds 128 bits")),
+        }
+    }
+
+    fn decode_simple(&mut self, info: u8) -> Result<DocumentValue> {
+        match info {
+            SIMPLE_FALSE => Ok(DocumentValue::Bool(false)),
+            SIMPLE_TRUE => Ok(DocumentValue::Bool(true)),
+            SIMPLE_NULL => Ok(DocumentValue::Null),
+            SIMPLE_UNDEFINED if !self.canonical => Ok(DocumentValue::Null),
+            SIMPLE_FLOAT_16 => {
+                let half = u16::from_be_bytes(self.read_array()?);
+                let value = f16_to_f64(half);
+                if self.canonical && value.is_nan() && half != CANONICAL_NAN {
+                    return Err(Error::non_canonical_cbor("NaN isn't in the canonical form"));
+                }
+                Ok(DocumentValue::Float(value))
+            }
+            SIMPLE_FLOAT_32 => {
+                let single = f32::from_bits(u32::from_be_bytes(self.read_array()?));
+                if self.canonical && (single.is_nan() || f32_to_f16(single).is_some()) {
+                    return Err(Error::non_canonical_cbor(
+                        "float isn't in the shortest form",
+                    ));
+                }
+                Ok(DocumentValue::Float(single as f64))
+            }
+            SIMPLE_FLOAT_64 => {
+                let double = f64::from_bits(u64::from_be_bytes(self.read_array()?));
+                if self.canonical && (double.is_nan() || double as f32 as f64 == double) {
+                    return Err(Error::non_canonical_cbor(
+                        "float isn't in the shortest form",
+                    ));
+                }
+                Ok(DocumentValue::Float(double))
+            }
+            _ => Err(Error::cbor(format!("unsupported simple value {info}"))),
+        }
+    }
+
+    fn read_text(&mut self, info: u8) -> Result<String> {
+        let bytes = self.read_string(MAJOR_TEXT, info)?;
+        String::from_utf8(bytes).map_err(|_| Error::cbor("text string isn't valid UTF-8"))
+    }
+
+    // Reads the content of a byte or text string, joining the chunks of indefinite strings
+    fn read_string(&mut self, major: u8, info: u8) -> Result<Vec<u8>> {
+        if info != INDEFINITE {
+            let len = self.read_length(info)?;
+            return Ok(self.read_slice(len)?.to_vec());
+        }
+
+        self.check_definite()?;
+        let mut result = Vec::new();
+        while !self.consume_break()? {
+            let initial = self.read_u8()?;
+            if initial >> 5 != major || initial & 0x1f == INDEFINITE {
+                return Err(Error::cbor("invalid chunk in indefinite length string"));
+            }
+            let len = self.read_length(initial & 0x1f)?;
+            result.extend_from_slice(self.read_slice(len)?);
+        }
+        Ok(result)
+    }
+
+    fn read_length(&mut self, info: u8) -> Result<usize> {
+        let len = self.read_argument(info)?;
+        usize::try_from(len).map_err(|_| Error::cbor("length doesn't fit into usize"))
+    }
+
+    fn read_argument(&mut self, info: u8) -> Result<u64> {
+        let (value, minimum) = match info {
+            0..=23 => return Ok(info as u64),
+            24 => (self.read_u8()? as u64, 24),
+            25 => (
+                u16::from_be_bytes(self.read_array()?) as u64,
+                u8::MAX as u64 + 1,
+            ),
+            26 => (
+                u32::from_be_bytes(self.read_array()?) as u64,
+                u16::MAX as u64 + 1,
+            ),
+            27 => (u64::from_be_bytes(self.read_array()?), u32::MAX as u64 + 1),
+            _ => {
+                return Err(Error::cbor(format!(
+                    "invalid additional information {info}"
+                )))
+            }
+        };
+        if self.canonical && value < minimum {
+            return Err(Error::non_canonical_cbor(format!(
+                "argument {value} isn't in the shortest form"
+            )));
+        }
+        Ok(value)
+    }
+
+    fn check_definite(&self) -> Result<()> {
+        if self.canonical {
+            return Err(Error::non_canonical_cbor("indefinite length item"));
+        }
+        Ok(())
+    }
+
+    fn consume_break(&mut self) -> Result<bool> {
+        match self.data.get(self.position) {
+            Some(&BREAK) => {
+                self.position += 1;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => Err(Error::cbor("unexpected end of input")),
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0_u8; N];
+        array.copy_from_slice(self.read_slice(N)?);
+        Ok(array)
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.remaining() {
+            return Err(Error::cbor("unexpected end of input"));
+        }
+        let slice = &self.data[self.position..self.position + len];
+        self.position += len;
+        Ok(slice)
+    }
+}
+
+fn f16_to_f64(half: u16) -> f64 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f64;
+
+    let value = match exponent {
+        0 => mantissa * 2_f64.powi(-24),
+        0x1f if mantissa == 0.0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => (mantissa + 1024.0) * 2_f64.powi(exponent - 25),
+    };
+    sign * value
+}
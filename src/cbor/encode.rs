@@ -0,0 +1,165 @@
+use crate::types::DocumentValue;
+
+use super::{
+    MAJOR_ARRAY, MAJOR_BYTES, MAJOR_MAP, MAJOR_NEGATIVE, MAJOR_SIMPLE, MAJOR_TAG, MAJOR_TEXT,
+    MAJOR_UNSIGNED, SIMPLE_FALSE, SIMPLE_FLOAT_16, SIMPLE_FLOAT_32, SIMPLE_FLOAT_64, SIMPLE_NULL,
+    SIMPLE_TRUE, TAG_NEGATIVE_BIGNUM, TAG_POSITIVE_BIGNUM,
+};
+
+// Canonical NaN used by RFC 8949 deterministic encoding
+pub(super) const CANONICAL_NAN: u16 = 0x7e00;
+
+pub(super) fn encode_value(value: &DocumentValue, out: &mut Vec<u8>) {
+    match value {
+        DocumentValue::Null => out.push(MAJOR_SIMPLE << 5 | SIMPLE_NULL),
+        DocumentValue::Bool(false) => out.push(MAJOR_SIMPLE << 5 | SIMPLE_FALSE),
+        DocumentValue::Bool(true) => out.push(MAJOR_SIMPLE << 5 | SIMPLE_TRUE),
+        DocumentValue::Integer(i) => encode_i128(*i as i128, out),
+        DocumentValue::UInteger(u) => write_head(MAJOR_UNSIGNED, *u, out),
+        DocumentValue::Integer128(i) => encode_i128(*i, out),
+        DocumentValue::UInteger128(u) => encode_u128(*u, out),
+        DocumentValue::Version(v) => write_head(MAJOR_UNSIGNED, *v as u64, out),
+        DocumentValue::Float(f) => encode_f64(*f, out),
+        DocumentValue::String(s) => {
+            write_head(MAJOR_TEXT, s.len() as u64, out);
+            out.extend_from_slice(s.as_bytes());
+        }
+        DocumentValue::Bytes(b) => encode_bytes(b, out),
+        DocumentValue::StaticBytes(b) => encode_bytes(&b.0, out),
+        DocumentValue::Identifier(id) => encode_bytes(&id.data, out),
+        DocumentValue::Array(array) => {
+            write_head(MAJOR_ARRAY, array.len() as u64, out);
+            for item in array {
+                encode_value(item, out);
+            }
+        }
+        DocumentValue::Map(map) => {
+            // the map keeps keys in the canonical order
+            write_head(MAJOR_MAP, map.len() as u64, out);
+            for (key, value) in map {
+                write_head(MAJOR_TEXT, key.len() as u64, out);
+                out.extend_from_slice(key.as_bytes());
+                encode_value(value, out);
+            }
+        }
+    }
+}
+
+/// Writes the initial byte and the argument using the shortest possible form
+pub(super) fn write_head(major: u8, value: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    if value < 24 {
+        out.push(major | value as u8);
+    } else if value <= u8::MAX as u64 {
+        out.push(major | 24);
+        out.push(value as u8);
+    } else if value <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(value as u16).to_be_bytes());
+    } else if value <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(value as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    write_head(MAJOR_BYTES, bytes.len() as u64, out);
+    out.extend_from_slice(bytes);
+}
+
+fn encode_u128(value: u128, out: &mut Vec<u8>) {
+    match u64::try_from(value) {
+        Ok(v) => write_head(MAJOR_UNSIGNED, v, out),
+        Err(_) => encode_bignum(TAG_POSITIVE_BIGNUM, value, out),
+    }
+}
+
+fn encode_i128(value: i128, out: &mut Vec<u8>) {
+    if value >= 0 {
+        return encode_u128(value as u128, out);
+    }
+    // negative integers are encoded as `-1 - n`
+    let n = (-1 - value) as u128;
+    match u64::try_from(n) {
+        Ok(v) => write_head(MAJOR_NEGATIVE, v, out),
+        Err(_) => encode_bignum(TAG_NEGATIVE_BIGNUM, n, out),
+    }
+}
+
+fn encode_bignum(tag: u64, value: u128, out: &mut Vec<u8>) {
+    write_head(MAJOR_TAG, tag, out);
+    let bytes = value.to_be_bytes();
+    let first_significant = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    encode_bytes(&bytes[first_significant..], out);
+}
+
+// Floats use the shortest of half, single and double precision that keeps the value
+fn encode_f64(value: f64, out: &mut Vec<u8>) {
+    if value.is_nan() {
+        out.push(MAJOR_SIMPLE << 5 | SIMPLE_FLOAT_16);
+        out.extend_from_slice(&CANONICAL_NAN.to_be_bytes());
+        return;
+    }
+
+    let single = value as f32;
+    if single as f64 != value {
+        out.push(MAJOR_SIMPLE << 5 | SIMPLE_FLOAT_64);
+        out.extend_from_slice(&value.to_bits().to_be_bytes());
+        return;
+    }
+
+    match f32_to_f16(single) {
+        Some(half) => {
+            out.push(MAJOR_SIMPLE << 5 | SIMPLE_FLOAT_16);
+            out.extend_from_slice(&half.to_be_bytes());
+        }
+        None => {
+            out.push(MAJOR_SIMPLE << 5 | SIMPLE_FLOAT_32);
+            out.extend_from_slice(&single.to_bits().to_be_bytes());
+        }
+    }
+}
+
+// Returns the half precision bits if the conversion is lossless
+pub(super) fn f32_to_f16(value: f32) -> Option<u16> {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        return match mantissa {
+            0 => Some(sign | 0x7c00),
+            _ => None,
+        };
+    }
+    if exponent == 0 {
+        // zero, f32 subnormals are too small for f16
+        return match mantissa {
+            0 => Some(sign),
+            _ => None,
+        };
+    }
+
+    let unbiased = exponent - 127;
+    if unbiased > 15 {
+        return None;
+    }
+    if unbiased >= -14 {
+        if mantissa & 0x1fff != 0 {
+            return None;
+        }
+        return Some(sign | (((unbiased + 15) as u16) << 10) | (mantissa >> 13) as u16);
+    }
+
+    // f16 subnormal: value = m * 2^-24
+    let significand = mantissa | 0x80_0000;
+    let shift = -(unbiased + 1);
+    if shift > 24 || significand & ((1 << shift) - 1) != 0 {
+        return None;
+    }
+    Some(sign | (significand >> shift) as u16)
+}
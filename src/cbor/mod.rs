@@ -0,0 +1,211 @@
+//! Canonical CBOR encoding of [`DocumentValue`].
+//!
+//! The canonical form uses the shortest encoding for integers, lengths and floats, definite
+//! lengths only, and map keys sorted by length and then bytewise. Integers which don't fit
+//! into 64 bits are written as bignums (tags 2 and 3). Binary variants are written as byte
+//! strings and are decoded as [`DocumentValue::Bytes`].
+mod decode;
+mod encode;
+
+pub use decode::Decoder;
+
+use crate::error::Error;
+use crate::types::DocumentValue;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+const SIMPLE_FALSE: u8 = 20;
+const SIMPLE_TRUE: u8 = 21;
+const SIMPLE_NULL: u8 = 22;
+const SIMPLE_UNDEFINED: u8 = 23;
+const SIMPLE_FLOAT_16: u8 = 25;
+const SIMPLE_FLOAT_32: u8 = 26;
+const SIMPLE_FLOAT_64: u8 = 27;
+
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+
+/// Encodes the value as canonical CBOR
+pub fn to_canonical_cbor(value: &DocumentValue) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode::encode_value(value, &mut out);
+    out
+}
+
+/// Decodes canonical CBOR, rejecting input which isn't in the canonical form
+pub fn from_canonical_cbor(data: &[u8]) -> Result<DocumentValue, Error> {
+    Decoder::new(data).with_canonical_check(true).decode()
+}
+
+/// Decodes any well-formed CBOR which can be represented as [`DocumentValue`]
+pub fn from_cbor(data: &[u8]) -> Result<DocumentValue, Error> {
+    Decoder::new(data).decode()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Error;
+    use crate::types::{Bytes, Identifier, StaticBytes, ValueMap};
+
+    fn assert_encoding(value: DocumentValue, expected_hex: &str) {
+        let encoded = to_canonical_cbor(&value);
+        assert_eq!(expected_hex, hex::encode(&encoded), "encoding of {value:?}");
+
+        let decoded = from_canonical_cbor(&encoded).expect("no error");
+        match (&value, &decoded) {
+            (DocumentValue::Float(a), DocumentValue::Float(b)) if a.is_nan() => {
+                assert!(b.is_nan())
+            }
+            _ => assert_eq!(value, decoded),
+        }
+    }
+
+    #[test]
+    fn rfc_8949_vectors() {
+        assert_encoding(DocumentValue::UInteger(0), "00");
+        assert_encoding(DocumentValue::UInteger(23), "17");
+        assert_encoding(DocumentValue::UInteger(24), "1818");
+        assert_encoding(DocumentValue::UInteger(1000), "1903e8");
+        assert_encoding(DocumentValue::UInteger(1000000), "1a000f4240");
+        assert_encoding(DocumentValue::UInteger(u64::MAX), "1bffffffffffffffff");
+        assert_encoding(
+            DocumentValue::UInteger128(u64::MAX as u128 + 1),
+            "c249010000000000000000",
+        );
+        assert_encoding(DocumentValue::Integer(-1), "20");
+        assert_encoding(DocumentValue::Integer(-1000), "3903e7");
+        assert_encoding(
+            DocumentValue::Integer128(-(u64::MAX as i128) - 2),
+            "c349010000000000000000",
+        );
+        assert_encoding(DocumentValue::Float(0.0), "f90000");
+        assert_encoding(DocumentValue::Float(-0.0), "f98000");
+        assert_encoding(DocumentValue::Float(1.5), "f93e00");
+        assert_encoding(DocumentValue::Float(65504.0), "f97bff");
+        assert_encoding(DocumentValue::Float(5.960464477539063e-8), "f90001");
+        assert_encoding(DocumentValue::Float(0.00006103515625), "f90400");
+        assert_encoding(DocumentValue::Float(-4.0), "f9c400");
+        assert_encoding(DocumentValue::Float(100000.0), "fa47c35000");
+        assert_encoding(DocumentValue::Float(3.4028234663852886e38), "fa7f7fffff");
+        assert_encoding(DocumentValue::Float(1.1), "fb3ff199999999999a");
+        assert_encoding(DocumentValue::Float(f64::INFINITY), "f97c00");
+        assert_encoding(DocumentValue::Float(f64::NEG_INFINITY), "f9fc00");
+        assert_encoding(DocumentValue::Float(f64::NAN), "f97e00");
+        assert_encoding(DocumentValue::Bool(false), "f4");
+        assert_encoding(DocumentValue::Bool(true), "f5");
+        assert_encoding(DocumentValue::Null, "f6");
+        assert_encoding(DocumentValue::String(String::from("a")), "6161");
+        assert_encoding(DocumentValue::Bytes(Bytes(vec![1, 2, 3, 4])), "4401020304");
+        assert_encoding(
+            DocumentValue::Array(vec![
+                DocumentValue::UInteger(1),
+                DocumentValue::UInteger(2),
+                DocumentValue::UInteger(3),
+            ]),
+            "83010203",
+        );
+    }
+
+    #[test]
+    fn map_keys_are_sorted() {
+        let mut map = ValueMap::new();
+        map.insert(String::from("bb"), DocumentValue::UInteger(3));
+        map.insert(String::from("b"), DocumentValue::UInteger(2));
+        map.insert(String::from("a"), DocumentValue::UInteger(1));
+        assert_encoding(DocumentValue::Map(map), "a361610161620262626203");
+    }
+
+    #[test]
+    fn binary_variants_are_byte_strings() {
+        let value = DocumentValue::Array(vec![
+            DocumentValue::Identifier(Identifier::from([1_u8; 32])),
+            DocumentValue::StaticBytes(StaticBytes([2_u8; 32])),
+            DocumentValue::Version(1),
+        ]);
+        let encoded = to_canonical_cbor(&value);
+        let decoded = from_canonical_cbor(&encoded).expect("no error");
+
+        assert_eq!(
+            DocumentValue::Array(vec![
+                DocumentValue::Bytes(Bytes(vec![1_u8; 32])),
+                DocumentValue::Bytes(Bytes(vec![2_u8; 32])),
+                DocumentValue::UInteger(1),
+            ]),
+            decoded
+        );
+    }
+
+    #[test]
+    fn matches_serde_cbor_for_documents() {
+        let value: DocumentValue = serde_json::json!({
+            "$type": "niceDocument",
+            "name": "Cutie",
+            "$revision": 1,
+            "price": 1.5,
+            "tags": ["a", "b"],
+            "nested": { "deep": -300, "big": 5000000000_u64 }
+        })
+        .try_into()
+        .expect("no error");
+
+        let expected = serde_cbor::to_vec(&value).expect("no error");
+        assert_eq!(expected, to_canonical_cbor(&value));
+    }
+
+    #[test]
+    fn rejects_non_canonical_input() {
+        for input in [
+            "1817",                 // 23 in the two-byte form
+            "190017",               // 23 in the three-byte form
+            "9f01ff",               // indefinite length array
+            "5f4101ff",             // indefinite length byte string
+            "a2616201616101",       // unsorted keys
+            "fb3ff8000000000000",   // 1.5 as a double
+            "fa3fc00000",           // 1.5 as a single
+            "f97e01",               // non-canonical NaN
+            "c2480100000000000000", // bignum which fits into 64 bits
+            "c1 1a514b67b0",        // unsupported tag
+        ] {
+            let bytes = hex::decode(input.replace(' ', "")).expect("no error");
+            assert!(
+                matches!(from_canonical_cbor(&bytes), Err(Error::NonCanonicalCbor(_))),
+                "{input} should be rejected"
+            );
+            assert!(from_cbor(&bytes).is_ok(), "{input} should be accepted");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for input in [
+            "",               // empty input
+            "1a0000",         // truncated argument
+            "62 61",          // truncated string
+            "a2616101616102", // duplicate key
+            "a10101",         // non-text key
+            "0000",           // trailing bytes
+            "62c328",         // invalid UTF-8
+            "9b ffffffffffffffff",
+        ] {
+            let bytes = hex::decode(input.replace(' ', "")).expect("no error");
+            assert!(
+                matches!(from_cbor(&bytes), Err(Error::CborError(_))),
+                "{input} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_deep_nesting() {
+        let bytes = vec![0x81_u8; 1000];
+        assert!(from_cbor(&bytes).is_err());
+    }
+}
@@ -0,0 +1,430 @@
+use serde::de::{
+    self,
+    value::{BorrowedStrDeserializer, MapDeserializer, SeqDeserializer},
+    Deserialize, DeserializeSeed, EnumAccess, IntoDeserializer, Unexpected, VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use super::{unexpected, FromDashValue};
+use crate::error::Error;
+use crate::tri;
+use crate::types::{DocumentValue as Value, ValueMap};
+
+type Result<K> = std::result::Result<K, Error>;
+
+impl FromDashValue {
+    /// Deserializes an instance of `T` borrowing from the value
+    pub fn deserialize_ref<'de, T>(self, value: &'de Value) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        T::deserialize(self.ref_deserializer(value))
+    }
+
+    fn ref_deserializer(self, value: &Value) -> RefDeserializer<'_> {
+        RefDeserializer {
+            value,
+            options: self,
+        }
+    }
+
+    fn visit_array_ref<'de, V>(self, array: &'de [Value], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut deserializer =
+            SeqDeserializer::new(array.iter().map(|value| self.ref_deserializer(value)));
+        let seq = tri!(visitor.visit_seq(&mut deserializer));
+        tri!(deserializer.end());
+        Ok(seq)
+    }
+
+    fn visit_map_ref<'de, V>(self, map: &'de ValueMap, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut deserializer = MapDeserializer::new(map.iter().map(|(key, value)| {
+            (
+                BorrowedStrDeserializer::new(key.as_str()),
+                self.ref_deserializer(value),
+            )
+        }));
+        let map = tri!(visitor.visit_map(&mut deserializer));
+        tri!(deserializer.end());
+        Ok(map)
+    }
+}
+
+fn visit_byte_array_ref<'de, V>(bytes: &'de [u8], visitor: V) -> Result<V::Value>
+where
+    V: Visitor<'de>,
+{
+    let mut deserializer = SeqDeserializer::new(bytes.iter().copied());
+    let seq = tri!(visitor.visit_seq(&mut deserializer));
+    tri!(deserializer.end());
+    Ok(seq)
+}
+
+impl<'de> serde::Deserializer<'de> for &'de Value {
+    type Error = Error;
+
+    // `&Value` deserializes with the default options
+    forward_to_deserializer! {
+        ref_deserializer:
+        deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16()
+        deserialize_i32() deserialize_i64() deserialize_i128() deserialize_u8()
+        deserialize_u16() deserialize_u32() deserialize_u64() deserialize_u128()
+        deserialize_f32() deserialize_f64() deserialize_char() deserialize_str()
+        deserialize_string() deserialize_bytes() deserialize_byte_buf() deserialize_option()
+        deserialize_unit() deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str) deserialize_seq()
+        deserialize_tuple(len: usize) deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_map() deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier() deserialize_ignored_any()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for &'de Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+// Deserializer borrowing from a `Value`. Strings and binary variants are given to the
+// visitor as borrowed data
+struct RefDeserializer<'de> {
+    value: &'de Value,
+    options: FromDashValue,
+}
+
+impl<'de> serde::Deserializer<'de> for RefDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::String(s) => visitor.visit_borrowed_str(s),
+            Value::Float(f) => visitor.visit_f64(*f),
+            Value::Integer(i) => visitor.visit_i64(*i),
+            Value::UInteger(u) => visitor.visit_u64(*u),
+            Value::Integer128(i) => visitor.visit_i128(*i),
+            Value::UInteger128(u) => visitor.visit_u128(*u),
+            Value::Version(v) => visitor.visit_u32(*v),
+            Value::Map(map) => self.options.visit_map_ref(map, visitor),
+            Value::Array(array) => self.options.visit_array_ref(array, visitor),
+            Value::Identifier(id) => visitor.visit_borrowed_bytes(&id.data),
+            Value::Bytes(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Value::StaticBytes(bytes) => visitor.visit_borrowed_bytes(&bytes.0),
+            Value::Null => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.options.decode_newtype(name, self.value) {
+            Some(bytes) => visitor.visit_byte_buf(tri!(bytes)),
+            None => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Array(array) => self.options.visit_array_ref(array, visitor),
+            Value::Identifier(id) => visit_byte_array_ref(&id.data, visitor),
+            Value::Bytes(bytes) => visit_byte_array_ref(bytes, visitor),
+            Value::StaticBytes(bytes) => visit_byte_array_ref(&bytes.0, visitor),
+            other => Err(de::Error::invalid_type(unexpected(other), &visitor)),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (variant, value) = match self.value {
+            Value::String(variant) => (variant.as_str(), None),
+            Value::Map(map) => {
+                let mut iter = map.iter();
+                let (variant, value) = match iter.next() {
+                    Some(entry) => entry,
+                    None => {
+                        return Err(de::Error::invalid_value(
+                            Unexpected::Map,
+                            &"map with a single key",
+                        ));
+                    }
+                };
+                if iter.next().is_some() {
+                    return Err(de::Error::invalid_value(
+                        Unexpected::Map,
+                        &"map with a single key",
+                    ));
+                }
+                (variant.as_str(), Some(value))
+            }
+            other => {
+                return Err(de::Error::invalid_type(unexpected(other), &"string or map"));
+            }
+        };
+
+        visitor.visit_enum(EnumRefDeserializer {
+            variant,
+            value,
+            options: self.options,
+        })
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.options.human_readable
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct map struct identifier
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for RefDeserializer<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+struct EnumRefDeserializer<'de> {
+    variant: &'de str,
+    value: Option<&'de Value>,
+    options: FromDashValue,
+}
+
+impl<'de> EnumAccess<'de> for EnumRefDeserializer<'de> {
+    type Error = Error;
+    type Variant = VariantRefDeserializer<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = tri!(seed.deserialize(BorrowedStrDeserializer::new(self.variant)));
+        Ok((
+            variant,
+            VariantRefDeserializer {
+                value: self.value,
+                options: self.options,
+            },
+        ))
+    }
+}
+
+struct VariantRefDeserializer<'de> {
+    value: Option<&'de Value>,
+    options: FromDashValue,
+}
+
+impl<'de> VariantAccess<'de> for VariantRefDeserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.value {
+            Some(value) => Deserialize::deserialize(self.options.ref_deserializer(value)),
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(self.options.ref_deserializer(value)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(Value::Array(array)) => self.options.visit_array_ref(array, visitor),
+            Some(other) => Err(de::Error::invalid_type(unexpected(other), &"tuple variant")),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(Value::Map(map)) => self.options.visit_map_ref(map, visitor),
+            Some(Value::Array(array)) => self.options.visit_array_ref(array, visitor),
+            Some(other) => Err(de::Error::invalid_type(
+                unexpected(other),
+                &"struct variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+
+    use crate::deserializer::{from_dash_value_ref, FromDashValue};
+    use crate::serializer::ToDashValue;
+    use crate::types::{
+        Bytes, DocumentValue, Encoding, Encodings, Identifier, StaticBytes, ValueMap, Version,
+    };
+
+    #[test]
+    fn deserialize_borrowed_fields() {
+        #[derive(Deserialize, Debug)]
+        struct Borrowed<'a> {
+            name: &'a str,
+            #[serde(borrow)]
+            data: &'a [u8],
+            id: Identifier,
+            version: Version,
+        }
+
+        let mut map = ValueMap::new();
+        map.insert(
+            String::from("name"),
+            DocumentValue::String(String::from("alpha")),
+        );
+        map.insert(
+            String::from("data"),
+            DocumentValue::Bytes(vec![1_u8, 2, 3].into()),
+        );
+        map.insert(
+            String::from("id"),
+            DocumentValue::Identifier(Identifier::from([9_u8; 32])),
+        );
+        map.insert(String::from("version"), DocumentValue::Version(1));
+        let value = DocumentValue::Map(map);
+
+        let borrowed: Borrowed = from_dash_value_ref(&value).expect("no errors");
+        assert_eq!("alpha", borrowed.name);
+        assert_eq!(&[1_u8, 2, 3], borrowed.data);
+        assert_eq!(Identifier::from([9_u8; 32]), borrowed.id);
+        assert_eq!(Version(1), borrowed.version);
+    }
+
+    #[test]
+    fn deserialize_ref_with_encodings() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        enum Transition {
+            Create(Identifier),
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Example<'a> {
+            name: &'a str,
+            id: Identifier,
+            data: Bytes,
+            entropy: StaticBytes,
+            nested: Vec<Transition>,
+        }
+
+        let name = String::from("alpha");
+        let example = Example {
+            name: &name,
+            id: Identifier::from([1_u8; 32]),
+            data: Bytes(vec![0xfb, 0xff]),
+            entropy: StaticBytes([2_u8; 32]),
+            nested: vec![Transition::Create(Identifier::from([3_u8; 32]))],
+        };
+
+        let encodings = Encodings::all(Encoding::Hex);
+        let value = example
+            .serialize(ToDashValue::human_readable().with_encodings(encodings))
+            .expect("no errors");
+        let options = FromDashValue::human_readable().with_encodings(encodings);
+        assert_eq!(example, options.deserialize_ref(&value).expect("no errors"));
+        assert!(from_dash_value_ref::<Example>(&value).is_err());
+
+        let value = example
+            .serialize(ToDashValue::default().with_encodings(encodings))
+            .expect("no errors");
+        let options = FromDashValue::default().with_encodings(encodings);
+        assert_eq!(example, options.deserialize_ref(&value).expect("no errors"));
+
+        let value = example
+            .serialize(ToDashValue::human_readable())
+            .expect("no errors");
+        assert_eq!(
+            example,
+            FromDashValue::human_readable()
+                .deserialize_ref(&value)
+                .expect("no errors")
+        );
+    }
+}
@@ -0,0 +1,511 @@
+use serde::de::{
+    self,
+    value::{MapDeserializer, SeqDeserializer},
+    DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, Unexpected, VariantAccess,
+    Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use super::unexpected;
+use crate::error::Error;
+use crate::tri;
+use crate::types::{DocumentValue as Value, Encodings, ValueMap};
+
+type Result<K> = std::result::Result<K, Error>;
+
+/// Options for reading typed values from a [`DocumentValue`](Value), the counterpart of
+/// [`ToDashValue`](crate::serializer::ToDashValue)
+#[derive(Default, Debug, Clone, Copy)]
+pub struct FromDashValue {
+    pub(super) human_readable: bool,
+    pub(super) encodings: Option<Encodings>,
+}
+
+impl FromDashValue {
+    /// Reads the values produced by [`ToDashValue::human_readable`]: identifiers and bytes are
+    /// base58 and base64 strings, and the deserializer reports itself as human-readable
+    ///
+    /// [`ToDashValue::human_readable`]: crate::serializer::ToDashValue::human_readable
+    pub fn human_readable() -> Self {
+        Self {
+            human_readable: true,
+            encodings: Some(Encodings::default()),
+        }
+    }
+
+    /// Identifiers and bytes stored as `Value::String` are decoded with given encodings
+    pub fn with_encodings(mut self, encodings: Encodings) -> Self {
+        self.encodings = Some(encodings);
+        self
+    }
+
+    pub fn deserialize<T>(self, value: Value) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        T::deserialize(self.deserializer(value))
+    }
+
+    fn deserializer(self, value: Value) -> ValueDeserializer {
+        ValueDeserializer {
+            value,
+            options: self,
+        }
+    }
+
+    pub(super) fn decode_newtype(self, name: &str, value: &Value) -> Option<Result<Vec<u8>>> {
+        let encodings = self.encodings?;
+        match (name, value) {
+            ("identifier", Value::String(s)) => Some(encodings.identifier.decode(s)),
+            ("Bytes" | "StaticBytes", Value::String(s)) => Some(encodings.bytes.decode(s)),
+            _ => None,
+        }
+    }
+
+    fn visit_array<'de, V>(self, array: Vec<Value>, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut deserializer =
+            SeqDeserializer::new(array.into_iter().map(|value| self.deserializer(value)));
+        let seq = tri!(visitor.visit_seq(&mut deserializer));
+        tri!(deserializer.end());
+        Ok(seq)
+    }
+
+    fn visit_map<'de, V>(self, map: ValueMap, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut deserializer = MapDeserializer::new(
+            map.into_iter()
+                .map(|(key, value)| (key, self.deserializer(value))),
+        );
+        let map = tri!(visitor.visit_map(&mut deserializer));
+        tri!(deserializer.end());
+        Ok(map)
+    }
+}
+
+fn visit_byte_array<'de, V>(bytes: Vec<u8>, visitor: V) -> Result<V::Value>
+where
+    V: Visitor<'de>,
+{
+    let mut deserializer = SeqDeserializer::new(bytes.into_iter());
+    let seq = tri!(visitor.visit_seq(&mut deserializer));
+    tri!(deserializer.end());
+    Ok(seq)
+}
+
+impl<'de> serde::Deserializer<'de> for Value {
+    type Error = Error;
+
+    // `Value` deserializes with the default options
+    forward_to_deserializer! {
+        deserializer:
+        deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16()
+        deserialize_i32() deserialize_i64() deserialize_i128() deserialize_u8()
+        deserialize_u16() deserialize_u32() deserialize_u64() deserialize_u128()
+        deserialize_f32() deserialize_f64() deserialize_char() deserialize_str()
+        deserialize_string() deserialize_bytes() deserialize_byte_buf() deserialize_option()
+        deserialize_unit() deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str) deserialize_seq()
+        deserialize_tuple(len: usize) deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_map() deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier() deserialize_ignored_any()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+// Deserializer reading from an owned `Value`. Binary variants are given to the visitor
+// as bytes, or as a sequence of `u8` when the visitor asks for a sequence
+struct ValueDeserializer {
+    value: Value,
+    options: FromDashValue,
+}
+
+impl<'de> serde::Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::String(s) => visitor.visit_string(s),
+            Value::Float(f) => visitor.visit_f64(f),
+            Value::Integer(i) => visitor.visit_i64(i),
+            Value::UInteger(u) => visitor.visit_u64(u),
+            Value::Integer128(i) => visitor.visit_i128(i),
+            Value::UInteger128(u) => visitor.visit_u128(u),
+            Value::Version(v) => visitor.visit_u32(v),
+            Value::Map(map) => self.options.visit_map(map, visitor),
+            Value::Array(array) => self.options.visit_array(array, visitor),
+            Value::Identifier(id) => visitor.visit_byte_buf(id.to_vec()),
+            Value::Bytes(bytes) => visitor.visit_byte_buf(bytes.0),
+            Value::StaticBytes(bytes) => visitor.visit_bytes(&bytes.0),
+            Value::Null => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    // the names are the ones `ToDashValue` recognizes, strings under them are decoded
+    // with the encodings the value was written with
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.options.decode_newtype(name, &self.value) {
+            Some(bytes) => visitor.visit_byte_buf(tri!(bytes)),
+            None => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Array(array) => self.options.visit_array(array, visitor),
+            Value::Identifier(id) => visit_byte_array(id.to_vec(), visitor),
+            Value::Bytes(bytes) => visit_byte_array(bytes.0, visitor),
+            Value::StaticBytes(bytes) => visit_byte_array(bytes.0.to_vec(), visitor),
+            other => Err(de::Error::invalid_type(unexpected(&other), &visitor)),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let (variant, value) = match self.value {
+            Value::String(variant) => (variant, None),
+            Value::Map(map) => {
+                let mut iter = map.into_iter();
+                let (variant, value) = match iter.next() {
+                    Some(entry) => entry,
+                    None => {
+                        return Err(de::Error::invalid_value(
+                            Unexpected::Map,
+                            &"map with a single key",
+                        ));
+                    }
+                };
+                if iter.next().is_some() {
+                    return Err(de::Error::invalid_value(
+                        Unexpected::Map,
+                        &"map with a single key",
+                    ));
+                }
+                (variant, Some(value))
+            }
+            other => {
+                return Err(de::Error::invalid_type(
+                    unexpected(&other),
+                    &"string or map",
+                ));
+            }
+        };
+
+        visitor.visit_enum(EnumDeserializer {
+            variant,
+            value,
+            options: self.options,
+        })
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        drop(self);
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.options.human_readable
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct map struct identifier
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Option<Value>,
+    options: FromDashValue,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantDeserializer)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = tri!(seed.deserialize(self.variant.into_deserializer()));
+        Ok((
+            variant,
+            VariantDeserializer {
+                value: self.value,
+                options: self.options,
+            },
+        ))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<Value>,
+    options: FromDashValue,
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.value {
+            Some(value) => serde::Deserialize::deserialize(self.options.deserializer(value)),
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(self.options.deserializer(value)),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(Value::Array(array)) => self.options.visit_array(array, visitor),
+            Some(other) => Err(de::Error::invalid_type(
+                unexpected(&other),
+                &"tuple variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(Value::Map(map)) => self.options.visit_map(map, visitor),
+            Some(Value::Array(array)) => self.options.visit_array(array, visitor),
+            Some(other) => Err(de::Error::invalid_type(
+                unexpected(&other),
+                &"struct variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+
+    use crate::deserializer::from_dash_value;
+    use crate::serializer::ToDashValue;
+    use crate::types::{Bytes, DocumentValue, Identifier, StaticBytes, ValueMap, Version};
+
+    #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Example {
+        id: Identifier,
+        owner_id: Identifier,
+        protocol_version: Version,
+        binary_data: Bytes,
+        entropy: StaticBytes,
+        revision: u32,
+        created_at: Option<i64>,
+        name: String,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn deserialize_struct_with_binary_types() {
+        let example = Example {
+            id: Identifier::from([1_u8; 32]),
+            owner_id: Identifier::from([2_u8; 32]),
+            protocol_version: Version(1),
+            binary_data: Bytes(vec![3_u8; 10]),
+            entropy: StaticBytes([4_u8; 32]),
+            revision: 2,
+            created_at: Some(1_668_000_000),
+            name: String::from("alpha"),
+            tags: vec![String::from("bravo")],
+        };
+
+        let value = example
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        let deserialized: Example = from_dash_value(value).expect("no errors");
+
+        assert_eq!(example, deserialized);
+    }
+
+    #[test]
+    fn deserialize_binary_variants() {
+        let mut map = ValueMap::new();
+        map.insert(
+            String::from("id"),
+            DocumentValue::Identifier(Identifier::from([5_u8; 32])),
+        );
+        map.insert(
+            String::from("ownerId"),
+            DocumentValue::Bytes(Bytes(vec![6_u8; 32])),
+        );
+        map.insert(String::from("protocolVersion"), DocumentValue::Version(3));
+        map.insert(
+            String::from("binaryData"),
+            DocumentValue::StaticBytes(StaticBytes([7_u8; 32])),
+        );
+        map.insert(
+            String::from("entropy"),
+            DocumentValue::Bytes(Bytes(vec![8_u8; 32])),
+        );
+        map.insert(String::from("revision"), DocumentValue::UInteger(1));
+        map.insert(String::from("createdAt"), DocumentValue::Null);
+        map.insert(
+            String::from("name"),
+            DocumentValue::String(String::from("charlie")),
+        );
+        map.insert(String::from("tags"), DocumentValue::Array(vec![]));
+
+        let deserialized: Example = from_dash_value(DocumentValue::Map(map)).expect("no errors");
+
+        assert_eq!(Identifier::from([5_u8; 32]), deserialized.id);
+        assert_eq!(Identifier::from([6_u8; 32]), deserialized.owner_id);
+        assert_eq!(Version(3), deserialized.protocol_version);
+        assert_eq!(Bytes(vec![7_u8; 32]), deserialized.binary_data);
+        assert_eq!(StaticBytes([8_u8; 32]), deserialized.entropy);
+        assert_eq!(None, deserialized.created_at);
+    }
+
+    #[test]
+    fn deserialize_enums() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum Action {
+            Delete,
+            Create(Identifier),
+            Move(i64, i64),
+            Replace { revision: u32 },
+        }
+
+        let value = DocumentValue::String(String::from("Delete"));
+        assert_eq!(Action::Delete, from_dash_value(value).expect("no errors"));
+
+        let mut map = ValueMap::new();
+        map.insert(
+            String::from("Create"),
+            DocumentValue::Identifier(Identifier::from([1_u8; 32])),
+        );
+        assert_eq!(
+            Action::Create(Identifier::from([1_u8; 32])),
+            from_dash_value(DocumentValue::Map(map)).expect("no errors")
+        );
+
+        let mut map = ValueMap::new();
+        map.insert(
+            String::from("Move"),
+            DocumentValue::Array(vec![DocumentValue::Integer(-1), DocumentValue::UInteger(2)]),
+        );
+        assert_eq!(
+            Action::Move(-1, 2),
+            from_dash_value(DocumentValue::Map(map)).expect("no errors")
+        );
+
+        let mut inner = ValueMap::new();
+        inner.insert(String::from("revision"), DocumentValue::UInteger(4));
+        let mut map = ValueMap::new();
+        map.insert(String::from("Replace"), DocumentValue::Map(inner));
+        assert_eq!(
+            Action::Replace { revision: 4 },
+            from_dash_value(DocumentValue::Map(map)).expect("no errors")
+        );
+    }
+
+    #[test]
+    fn invalid_type_is_an_error() {
+        let result: Result<Example, _> = from_dash_value(DocumentValue::Bool(true));
+        assert!(result.is_err());
+
+        let result: Result<u8, _> = from_dash_value(DocumentValue::UInteger(256));
+        assert!(result.is_err());
+    }
+}
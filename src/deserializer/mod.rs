@@ -0,0 +1,86 @@
+// Implements the `Deserializer` methods by creating the deserializer with the default options
+macro_rules! forward_to_deserializer {
+    ($deserializer:ident: $($method:ident($($arg:ident: $ty:ty),*))*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                FromDashValue::default()
+                    .$deserializer(self)
+                    .$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+mod from_ref;
+mod from_value;
+
+pub use from_value::FromDashValue;
+
+use serde::{de::DeserializeOwned, de::Unexpected, Deserialize};
+
+use crate::prelude::*;
+use crate::tri;
+
+/// Deserializes an instance of `T` from the [`DocumentValue`]
+pub fn from_dash_value<T>(value: DocumentValue) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(value)
+}
+
+/// Deserializes an instance of `T` from the [`DocumentValue`] produced by
+/// [`ToDashValue::human_readable`](crate::serializer::ToDashValue::human_readable)
+pub fn from_dash_value_human_readable<T>(value: DocumentValue) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    FromDashValue::human_readable().deserialize(value)
+}
+
+/// Deserializes an instance of `T` borrowing from the [`DocumentValue`]
+pub fn from_dash_value_ref<'de, T>(value: &'de DocumentValue) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(value)
+}
+
+/// Deserializes an instance of `T` from any format where identifiers and bytes are strings
+/// with given encodings, the inverse of [`WithEncodings`](crate::serializer::WithEncodings)
+pub fn deserialize_with_encodings<'de, D, T>(
+    deserializer: D,
+    encodings: Encodings,
+) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = tri!(DocumentValue::deserialize(deserializer));
+    FromDashValue::default()
+        .with_encodings(encodings)
+        .deserialize(value)
+        .map_err(serde::de::Error::custom)
+}
+
+fn unexpected(value: &DocumentValue) -> Unexpected<'_> {
+    match value {
+        DocumentValue::Bool(b) => Unexpected::Bool(*b),
+        DocumentValue::String(s) => Unexpected::Str(s),
+        DocumentValue::Float(f) => Unexpected::Float(*f),
+        DocumentValue::Integer(i) => Unexpected::Signed(*i),
+        DocumentValue::UInteger(u) => Unexpected::Unsigned(*u),
+        DocumentValue::Integer128(_) => Unexpected::Other("128-bit integer"),
+        DocumentValue::UInteger128(_) => Unexpected::Other("128-bit unsigned integer"),
+        DocumentValue::Version(v) => Unexpected::Unsigned(*v as u64),
+        DocumentValue::Map(_) => Unexpected::Map,
+        DocumentValue::Array(_) => Unexpected::Seq,
+        DocumentValue::Identifier(id) => Unexpected::Bytes(&id.data),
+        DocumentValue::Bytes(b) => Unexpected::Bytes(b),
+        DocumentValue::StaticBytes(b) => Unexpected::Bytes(&b.0),
+        DocumentValue::Null => Unexpected::Unit,
+    }
+}
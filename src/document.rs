@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    cbor,
+    deserializer::from_dash_value,
+    envelope,
+    error::Error,
+    serializer::ToDashValue,
+    types::{Identifier, StaticBytes, ValueMap, Version},
+};
+
+/// Document of a data contract. The system properties (the ones prefixed with `$`) are typed
+/// fields, the remaining properties are kept in `data`.
+///
+/// JSON and CBOR have no notion of the binary variants of
+/// [`DocumentValue`](crate::types::DocumentValue), so the values in
+/// `data` come back from [`Document::from_bytes`] in their wire form: `Identifier` and
+/// `StaticBytes` as `Bytes`, `Version` as `UInteger`, and from [`Document::from_json`] with
+/// binary data as strings. The data contract schema restores them, see
+/// [`DocumentValue::arrays_as_bytes`](crate::types::DocumentValue::arrays_as_bytes)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TypedBuilder)]
+pub struct Document {
+    #[serde(rename = "$protocolVersion", default)]
+    #[builder(default = Version(1))]
+    pub protocol_version: Version,
+
+    #[serde(rename = "$id")]
+    #[builder(setter(into))]
+    pub id: Identifier,
+
+    #[serde(rename = "$type")]
+    #[builder(setter(into))]
+    pub document_type: String,
+
+    #[serde(rename = "$revision")]
+    #[builder(default = 0)]
+    pub revision: u32,
+
+    #[serde(rename = "$dataContractId")]
+    #[builder(setter(into))]
+    pub data_contract_id: Identifier,
+
+    #[serde(rename = "$ownerId")]
+    #[builder(setter(into))]
+    pub owner_id: Identifier,
+
+    #[serde(rename = "$createdAt", skip_serializing_if = "Option::is_none")]
+    #[builder(default = None)]
+    pub created_at: Option<i64>,
+
+    #[serde(rename = "$updatedAt", skip_serializing_if = "Option::is_none")]
+    #[builder(default = None)]
+    pub updated_at: Option<i64>,
+
+    #[serde(flatten)]
+    #[builder(default)]
+    pub data: ValueMap,
+
+    #[serde(skip)]
+    #[builder(default, setter(into))]
+    pub entropy: StaticBytes<32>,
+}
+
+impl Document {
+    /// Computes the id of the document from its contract, owner, type and entropy
+    pub fn generate_id(&self) -> Identifier {
+        Identifier::generate_document_id(
+            &self.data_contract_id,
+            &self.owner_id,
+            &self.document_type,
+            &self.entropy,
+        )
+    }
+
+    #[cfg(feature = "serde_json_value")]
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+
+    #[cfg(feature = "serde_json_value")]
+    pub fn from_json(data: impl AsRef<str>) -> Result<Document, Error> {
+        serde_json::from_str(data.as_ref()).map_err(|e| Error::DeserializationError(e.to_string()))
+    }
+
+    /// Encodes the document in the wire format: the protocol version prefix followed by
+    /// canonical CBOR
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let dynamic_value = self.serialize(ToDashValue::default().with_skip_version(true))?;
+        envelope::encode(
+            self.protocol_version,
+            &cbor::to_canonical_cbor(&dynamic_value),
+        )
+    }
+
+    /// Decodes the document from the wire format, restoring `$protocolVersion` from the prefix
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Document, Error> {
+        let (protocol_version, payload) = envelope::decode(bytes.as_ref())?;
+        let dynamic_value = cbor::from_canonical_cbor(payload)?;
+        let mut document: Document = from_dash_value(dynamic_value)?;
+        document.protocol_version = protocol_version;
+        Ok(document)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Document;
+    use crate::{
+        error::Error,
+        types::{BinaryKind, DocumentValue, Identifier, ValueMap, Version},
+    };
+
+    #[test]
+    fn test_document_builder() {
+        let document = Document::builder()
+            .owner_id([10_u8; 32])
+            .id([11_u8; 32])
+            .document_type(String::from("something"))
+            .data_contract_id([10_u8; 32])
+            .build();
+
+        assert_eq!(Version(1), document.protocol_version);
+        assert_eq!(Identifier::from([11_u8; 32]), document.id);
+        assert_eq!(Identifier::from([10_u8; 32]), document.owner_id);
+        assert_eq!(Identifier::from([10_u8; 32]), document.data_contract_id);
+        assert_eq!("something", document.document_type);
+        assert_eq!(0, document.revision);
+        assert_eq!(None, document.created_at);
+        assert_eq!(None, document.updated_at);
+        assert!(document.data.is_empty());
+        assert_eq!([0_u8; 32], *document.entropy);
+    }
+
+    #[test]
+    fn test_serialize_to_json() {
+        let document_bytes = hex::decode("01000000a7632469645820715d3d65756024a2de0ab1b2bb1e83b5ef297bf0c6fa616aad5c887e4f10def9646e616d656543757469656524747970656c6e696365446f63756d656e7468246f776e657249645820b6bf374d302fbe2b511b43e23d033f965e2e33a024c7419db07533d4ba7d708e69247265766973696f6e016a246372656174656441741b00000181b40fa1fb6f2464617461436f6e7472616374496458207abc5f9ab4bcd0612ed6cacec204dd6d7411a56127d4248af1eadacb93525da2").expect("no error");
+
+        let document = Document::from_bytes(&document_bytes).expect("no error");
+        assert_eq!(Version(1), document.protocol_version);
+        assert_eq!("niceDocument", document.document_type);
+        assert_eq!(1, document.revision);
+        assert_eq!(Some(1_656_583_332_347), document.created_at);
+        assert_eq!(DocumentValue::from("Cutie"), document.data["name"]);
+
+        let bytes = document.to_bytes().expect("no error");
+        assert_eq!(document_bytes, bytes);
+        assert_eq!(document, Document::from_bytes(&bytes).expect("no error"));
+    }
+
+    #[cfg(feature = "serde_json_value")]
+    #[test]
+    fn json_round_trip() {
+        let mut data = ValueMap::new();
+        data.insert("name", DocumentValue::String(String::from("Cutie")));
+        data.insert("age", DocumentValue::UInteger(3));
+
+        let document = Document::builder()
+            .id([1_u8; 32])
+            .document_type("niceDocument")
+            .revision(2)
+            .data_contract_id([2_u8; 32])
+            .owner_id([3_u8; 32])
+            .created_at(Some(1_668_000_000_000))
+            .data(data)
+            .build();
+
+        let json = document.to_json().expect("no error");
+        let value: serde_json::Value = serde_json::from_str(&json).expect("no error");
+        assert_eq!(
+            serde_json::Value::String(Identifier::from([1_u8; 32]).to_string()),
+            value["$id"]
+        );
+        assert_eq!("niceDocument", value["$type"]);
+        assert_eq!(1, value["$protocolVersion"]);
+
+        let decoded = Document::from_json(&json).expect("no error");
+        assert_eq!(document, decoded);
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut data = ValueMap::new();
+        data.insert("name", DocumentValue::String(String::from("Cutie")));
+
+        let document = Document::builder()
+            .id([1_u8; 32])
+            .document_type("niceDocument")
+            .data_contract_id([2_u8; 32])
+            .owner_id([3_u8; 32])
+            .updated_at(Some(1_668_000_000_000))
+            .data(data)
+            .build();
+
+        let bytes = document.to_bytes().expect("no error");
+        assert_eq!(&[1, 0, 0, 0], &bytes[..4]);
+
+        let decoded = Document::from_bytes(bytes).expect("no error");
+        assert_eq!(document, decoded);
+    }
+
+    fn default_document() -> Document {
+        Document::builder()
+            .id([1_u8; 32])
+            .document_type("niceDocument")
+            .data_contract_id([2_u8; 32])
+            .owner_id([3_u8; 32])
+            .build()
+    }
+
+    #[test]
+    fn default_document_round_trip() {
+        let document = default_document();
+        let decoded = Document::from_bytes(document.to_bytes().expect("no error"));
+        assert_eq!(document, decoded.expect("no error"));
+    }
+
+    #[cfg(feature = "serde_json_value")]
+    #[test]
+    fn default_document_json_round_trip() {
+        let document = default_document();
+        let decoded = Document::from_json(document.to_json().expect("no error"));
+        assert_eq!(document, decoded.expect("no error"));
+    }
+
+    #[test]
+    fn binary_data_round_trip() {
+        let mut data = ValueMap::new();
+        data.insert("avatar", DocumentValue::Bytes(vec![1, 2, 3].into()));
+        data.insert("friend", DocumentValue::Identifier([4_u8; 32].into()));
+        data.insert("version", DocumentValue::Version(2));
+
+        let document = Document::builder()
+            .id([1_u8; 32])
+            .document_type("niceDocument")
+            .data_contract_id([2_u8; 32])
+            .owner_id([3_u8; 32])
+            .data(data)
+            .build();
+
+        let decoded =
+            Document::from_bytes(document.to_bytes().expect("no error")).expect("no error");
+        assert_eq!(document.data["avatar"], decoded.data["avatar"]);
+        // the binary variants come back in their wire form
+        assert_eq!(
+            DocumentValue::Bytes(vec![4_u8; 32].into()),
+            decoded.data["friend"]
+        );
+        assert_eq!(DocumentValue::UInteger(2), decoded.data["version"]);
+
+        let restored = DocumentValue::Map(decoded.data)
+            .arrays_as_bytes([("friend", BinaryKind::Identifier)])
+            .expect("no error");
+        assert_eq!(document.data["friend"], restored["friend"]);
+    }
+
+    #[test]
+    fn generate_id_from_entropy() {
+        let mut document = Document::builder()
+            .id(Identifier::default())
+            .document_type("niceDocument")
+            .data_contract_id([2_u8; 32])
+            .owner_id([3_u8; 32])
+            .entropy([4_u8; 32])
+            .build();
+        document.id = document.generate_id();
+
+        assert_eq!(
+            Identifier::generate_document_id(
+                &Identifier::from([2_u8; 32]),
+                &Identifier::from([3_u8; 32]),
+                "niceDocument",
+                &[4_u8; 32]
+            ),
+            document.id
+        );
+    }
+
+    #[test]
+    fn bytes_with_unknown_version() {
+        let document = Document::builder()
+            .protocol_version(Version(7))
+            .id([1_u8; 32])
+            .document_type("niceDocument")
+            .data_contract_id([2_u8; 32])
+            .owner_id([3_u8; 32])
+            .build();
+
+        assert!(matches!(
+            document.to_bytes(),
+            Err(Error::UnknownProtocolVersion(7))
+        ));
+        assert!(matches!(
+            Document::from_bytes([7, 0, 0, 0, 0xa0]),
+            Err(Error::UnknownProtocolVersion(7))
+        ));
+        assert!(matches!(
+            Document::from_bytes([1]),
+            Err(Error::TruncatedEnvelope { .. })
+        ));
+    }
+}
@@ -0,0 +1,94 @@
+//! Binary envelope used by DPP on the wire: the protocol version encoded as a 4-byte
+//! little-endian integer followed by the payload (canonical CBOR for documents)
+
+use crate::{error::Error, types::Version};
+
+/// Size of the protocol version prefix
+pub const PROTOCOL_VERSION_SIZE: usize = 4;
+
+/// Latest protocol version known to this crate
+pub const LATEST_PROTOCOL_VERSION: Version = Version(1);
+
+/// Protocol versions accepted by [`encode`] and [`decode`]
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[Version] = &[LATEST_PROTOCOL_VERSION];
+
+pub fn is_supported(version: Version) -> bool {
+    SUPPORTED_PROTOCOL_VERSIONS.contains(&version)
+}
+
+/// Prefixes the payload with the protocol version
+pub fn encode(version: Version, payload: &[u8]) -> Result<Vec<u8>, Error> {
+    if !is_supported(version) {
+        return Err(Error::UnknownProtocolVersion(version.0));
+    }
+
+    let mut buf = Vec::with_capacity(PROTOCOL_VERSION_SIZE + payload.len());
+    buf.extend_from_slice(&version.0.to_le_bytes());
+    buf.extend_from_slice(payload);
+    Ok(buf)
+}
+
+/// Splits the envelope into the protocol version and the payload
+pub fn decode(bytes: &[u8]) -> Result<(Version, &[u8]), Error> {
+    if bytes.len() < PROTOCOL_VERSION_SIZE {
+        return Err(Error::TruncatedEnvelope {
+            expected: PROTOCOL_VERSION_SIZE,
+            found: bytes.len(),
+        });
+    }
+
+    let (prefix, payload) = bytes.split_at(PROTOCOL_VERSION_SIZE);
+    let mut version_bytes = [0_u8; PROTOCOL_VERSION_SIZE];
+    version_bytes.copy_from_slice(prefix);
+    let version = Version(u32::from_le_bytes(version_bytes));
+
+    if !is_supported(version) {
+        return Err(Error::UnknownProtocolVersion(version.0));
+    }
+    Ok((version, payload))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let bytes = encode(LATEST_PROTOCOL_VERSION, &[0xa0]).expect("no error");
+        assert_eq!(vec![1, 0, 0, 0, 0xa0], bytes);
+
+        let (version, payload) = decode(&bytes).expect("no error");
+        assert_eq!(LATEST_PROTOCOL_VERSION, version);
+        assert_eq!(&[0xa0], payload);
+    }
+
+    #[test]
+    fn empty_payload() {
+        let (version, payload) = decode(&[1, 0, 0, 0]).expect("no error");
+        assert_eq!(Version(1), version);
+        assert!(payload.is_empty());
+    }
+
+    #[test]
+    fn unknown_version() {
+        assert!(matches!(
+            encode(Version(7), &[]),
+            Err(Error::UnknownProtocolVersion(7))
+        ));
+        assert!(matches!(
+            decode(&[0, 0, 0, 0, 0xa0]),
+            Err(Error::UnknownProtocolVersion(0))
+        ));
+    }
+
+    #[test]
+    fn truncated() {
+        assert!(matches!(
+            decode(&[1, 0]),
+            Err(Error::TruncatedEnvelope {
+                expected: 4,
+                found: 2
+            })
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use serde::ser;
+use serde::{de, ser};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -10,6 +10,42 @@ pub enum Error {
 
     #[error("Unsupported: {0}")]
     Unsupported(String),
+
+    #[error("CBOR error: {0}")]
+    CborError(String),
+    #[error("non-canonical CBOR: {0}")]
+    NonCanonicalCbor(String),
+
+    #[error("invalid path '{path}' at segment '{segment}': {reason}")]
+    PathError {
+        path: String,
+        segment: String,
+        reason: String,
+    },
+
+    #[error("unexpected type: expected {expected}, got {found}")]
+    UnexpectedType { expected: String, found: String },
+
+    #[error("patch operation #{index} '{op}' at '{path}' failed: {reason}")]
+    PatchError {
+        index: usize,
+        op: String,
+        path: String,
+        reason: String,
+    },
+
+    #[error("value {value} is out of range of {target}")]
+    OutOfRange { value: String, target: String },
+
+    #[error("invalid length: expected {expected} bytes, got {found}")]
+    InvalidLength { expected: usize, found: usize },
+    #[error("invalid encoding: {0}")]
+    InvalidEncoding(String),
+
+    #[error("unknown protocol version: {0}")]
+    UnknownProtocolVersion(u32),
+    #[error("truncated envelope: expected at least {expected} bytes, got {found}")]
+    TruncatedEnvelope { expected: usize, found: usize },
 }
 
 impl Error {
@@ -20,6 +56,44 @@ impl Error {
     pub fn serialization(msg: &str) -> Self {
         Self::SerializationError(String::from(msg))
     }
+
+    pub fn unexpected_type(expected: &str, found: &str) -> Self {
+        Self::UnexpectedType {
+            expected: String::from(expected),
+            found: String::from(found),
+        }
+    }
+
+    pub fn out_of_range(value: impl std::fmt::Display, target: &str) -> Self {
+        Self::OutOfRange {
+            value: value.to_string(),
+            target: String::from(target),
+        }
+    }
+
+    pub fn cbor(msg: impl Into<String>) -> Self {
+        Self::CborError(msg.into())
+    }
+
+    pub fn non_canonical_cbor(msg: impl Into<String>) -> Self {
+        Self::NonCanonicalCbor(msg.into())
+    }
+
+    pub fn path(
+        path: impl Into<String>,
+        segment: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::PathError {
+            path: path.into(),
+            segment: segment.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn deserialization(msg: &str) -> Self {
+        Self::DeserializationError(String::from(msg))
+    }
 }
 
 impl ser::Error for Error {
@@ -30,3 +104,12 @@ impl ser::Error for Error {
         Error::SerializationError(format!("{:#}", msg))
     }
 }
+
+impl de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: std::fmt::Display,
+    {
+        Error::DeserializationError(format!("{:#}", msg))
+    }
+}
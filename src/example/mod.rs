@@ -1,2 +0,0 @@
-#[cfg(test)]
-mod document;
@@ -0,0 +1,236 @@
+//! Conversion of [`DocumentValue`] into [`serde_json::Value`] with an explicit policy for the
+//! values JSON can't represent: binary data, and integers which JavaScript can't represent
+//! exactly.
+use serde_json::Value as JsonValue;
+
+use crate::error::Error;
+use crate::types::{DocumentValue, Encoding, Location, Walk};
+
+/// The largest integer JavaScript can represent exactly, `2^53 - 1`
+pub const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Representation of a binary variant in JSON
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryPolicy {
+    /// String with the given encoding
+    Encoded(Encoding),
+    /// Array of integers, one per byte
+    Array,
+}
+
+/// Policy of [`DocumentValue::to_json_value`]. The default one gives the same output as
+/// `serde_json::to_value`: base58 identifiers, base64 bytes and numbers as they are
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JsonPolicy {
+    pub identifier: BinaryPolicy,
+    pub bytes: BinaryPolicy,
+    pub static_bytes: BinaryPolicy,
+    /// `Version` becomes a string
+    pub stringify_version: bool,
+    /// Integers outside of `-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER` become strings
+    pub stringify_unsafe_integers: bool,
+}
+
+impl Default for JsonPolicy {
+    fn default() -> Self {
+        Self {
+            identifier: BinaryPolicy::Encoded(Encoding::Base58),
+            bytes: BinaryPolicy::Encoded(Encoding::Base64),
+            static_bytes: BinaryPolicy::Encoded(Encoding::Base64),
+            stringify_version: false,
+            stringify_unsafe_integers: false,
+        }
+    }
+}
+
+impl JsonPolicy {
+    /// Default policy which additionally stringifies versions and integers that are unsafe
+    /// in JavaScript
+    pub fn js_safe() -> Self {
+        Self {
+            stringify_version: true,
+            stringify_unsafe_integers: true,
+            ..Default::default()
+        }
+    }
+
+    /// Uses the same policy for all binary variants
+    pub fn with_binary(self, policy: BinaryPolicy) -> Self {
+        Self {
+            identifier: policy,
+            bytes: policy,
+            static_bytes: policy,
+            ..self
+        }
+    }
+
+    pub fn with_identifier(mut self, policy: BinaryPolicy) -> Self {
+        self.identifier = policy;
+        self
+    }
+
+    pub fn with_bytes(mut self, policy: BinaryPolicy) -> Self {
+        self.bytes = policy;
+        self
+    }
+
+    pub fn with_static_bytes(mut self, policy: BinaryPolicy) -> Self {
+        self.static_bytes = policy;
+        self
+    }
+
+    pub fn with_stringify_version(mut self, stringify: bool) -> Self {
+        self.stringify_version = stringify;
+        self
+    }
+
+    pub fn with_stringify_unsafe_integers(mut self, stringify: bool) -> Self {
+        self.stringify_unsafe_integers = stringify;
+        self
+    }
+
+    // converts a leaf into the value JSON can represent
+    fn apply(&self, value: &mut DocumentValue) {
+        let binary_policy = match value {
+            DocumentValue::Identifier(_) => self.identifier,
+            DocumentValue::Bytes(_) => self.bytes,
+            DocumentValue::StaticBytes(_) => self.static_bytes,
+            DocumentValue::Version(v) if self.stringify_version => {
+                *value = DocumentValue::String(v.to_string());
+                return;
+            }
+            _ => {
+                if self.stringify_unsafe_integers && is_unsafe_integer(value) {
+                    if let Ok(i) = value.as_i128() {
+                        *value = DocumentValue::String(i.to_string());
+                    } else if let Ok(u) = value.as_u128() {
+                        *value = DocumentValue::String(u.to_string());
+                    }
+                }
+                return;
+            }
+        };
+
+        match binary_policy {
+            BinaryPolicy::Array => DocumentValue::replace_bytes_with_array(value),
+            BinaryPolicy::Encoded(encoding) => {
+                if let Ok(data) = value.as_bytes() {
+                    *value = DocumentValue::String(encoding.encode(data));
+                }
+            }
+        }
+    }
+}
+
+fn is_unsafe_integer(value: &DocumentValue) -> bool {
+    match value {
+        DocumentValue::Integer(i) => i.unsigned_abs() > MAX_SAFE_INTEGER,
+        DocumentValue::UInteger(u) => *u > MAX_SAFE_INTEGER,
+        DocumentValue::Integer128(_) | DocumentValue::UInteger128(_) => true,
+        _ => false,
+    }
+}
+
+impl DocumentValue {
+    /// Converts the value into JSON, representing binary data, versions and large integers
+    /// according to the policy. Integers that don't fit into 64 bits can only be converted
+    /// when stringified. Non-finite floats become `null`, the same as in `serde_json`
+    pub fn to_json_value(&self, policy: &JsonPolicy) -> Result<JsonValue, Error> {
+        let mut value = self.clone();
+        value.walk_mut(&mut |_: &Location, value: &mut DocumentValue| match value {
+            DocumentValue::Array(_) | DocumentValue::Map(_) => Walk::Continue,
+            _ => {
+                // binary data can become an array, which doesn't need another pass
+                policy.apply(value);
+                Walk::SkipChildren
+            }
+        });
+
+        serde_json::to_value(&value).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::value;
+
+    fn example() -> DocumentValue {
+        value!({
+            "$id": id([1_u8; 32]),
+            "$protocolVersion": version(1),
+            "avatar": bytes(vec![0xfb, 0xff]),
+            "entropy": static_bytes([2_u8; 32]),
+            "nested": [{ "big": u64::MAX, "small": -5, "negative": i64::MIN }],
+        })
+    }
+
+    #[test]
+    fn default_policy_is_serde_json() {
+        let value = example();
+        assert_eq!(
+            serde_json::to_value(&value).expect("no error"),
+            value
+                .to_json_value(&JsonPolicy::default())
+                .expect("no error")
+        );
+    }
+
+    #[test]
+    fn binary_as_arrays() {
+        let policy = JsonPolicy::default()
+            .with_bytes(BinaryPolicy::Array)
+            .with_identifier(BinaryPolicy::Encoded(Encoding::Hex));
+        let json = example().to_json_value(&policy).expect("no error");
+
+        assert_eq!(json!("01".repeat(32)), json["$id"]);
+        assert_eq!(json!([0xfb, 0xff]), json["avatar"]);
+        assert_eq!(json!(base64::encode([2_u8; 32])), json["entropy"]);
+
+        let json = example()
+            .to_json_value(&JsonPolicy::default().with_binary(BinaryPolicy::Array))
+            .expect("no error");
+        assert_eq!(
+            serde_json::to_value(example().bytes_as_arrays()).expect("no error"),
+            json
+        );
+    }
+
+    #[test]
+    fn js_safe() {
+        let json = example()
+            .to_json_value(&JsonPolicy::js_safe())
+            .expect("no error");
+
+        assert_eq!(json!("1"), json["$protocolVersion"]);
+        assert_eq!(json!(u64::MAX.to_string()), json["nested"][0]["big"]);
+        assert_eq!(json!(i64::MIN.to_string()), json["nested"][0]["negative"]);
+        assert_eq!(json!(-5), json["nested"][0]["small"]);
+
+        let json = value!([MAX_SAFE_INTEGER, MAX_SAFE_INTEGER + 1, u128::MAX])
+            .to_json_value(&JsonPolicy::js_safe())
+            .expect("no error");
+        assert_eq!(
+            json!([
+                MAX_SAFE_INTEGER,
+                (MAX_SAFE_INTEGER + 1).to_string(),
+                u128::MAX.to_string()
+            ]),
+            json
+        );
+    }
+
+    #[test]
+    fn large_integers_need_stringifying() {
+        let value = value!(u128::MAX);
+        assert!(value.to_json_value(&JsonPolicy::default()).is_err());
+        assert_eq!(
+            json!(u128::MAX.to_string()),
+            value
+                .to_json_value(&JsonPolicy::default().with_stringify_unsafe_integers(true))
+                .expect("no error")
+        );
+    }
+}
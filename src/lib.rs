@@ -1,10 +1,17 @@
+pub mod cbor;
+pub mod deserializer;
+pub mod document;
+pub mod envelope;
 mod error;
-mod example;
+#[cfg(feature = "serde_json_value")]
+pub mod json;
+mod macros;
+#[cfg(feature = "serde_json_value")]
+pub mod schema;
 pub mod serializer;
 pub mod types;
 
 pub use error::Error;
-use std::ops::Deref;
 
 mod prelude {
     pub use super::types::*;
@@ -27,10 +34,10 @@ macro_rules! tri {
 mod test {
     use crate::{serializer::ToDashValue, types::*};
     use serde::{Deserialize, Serialize};
-    use std::collections::HashMap;
 
     #[test]
     fn test_deserialize_from_json_str() {
+        #[allow(dead_code)]
         #[derive(Serialize, Deserialize)]
         struct ExampleDoc {
             id: String,
@@ -51,7 +58,7 @@ mod test {
             #[serde(flatten)]
             data: DocumentValue,
         }
-        let mut dynamic_data: HashMap<String, DocumentValue> = HashMap::new();
+        let mut dynamic_data: ValueMap = ValueMap::new();
         dynamic_data.insert(
             String::from("dynamic_bytes"),
             DocumentValue::Bytes(vec![2u8; 32].into()),
@@ -67,9 +74,7 @@ mod test {
         }
 
         let example = ExampleStruct {
-            id: Identifier {
-                data: vec![0u8; 32],
-            },
+            id: Identifier { data: [0u8; 32] },
             binary_data: Bytes(vec![2u8; 32]),
             data: DocumentValue::Map(dynamic_data),
             ..Default::default()
@@ -101,7 +106,7 @@ mod test {
             data: DocumentValue,
         }
 
-        let dynamic_a: HashMap<String, DocumentValue> = vec![
+        let dynamic_a: ValueMap = vec![
             (
                 String::from("property_a"),
                 DocumentValue::String(String::from("value_a")),
@@ -114,7 +119,7 @@ mod test {
         .into_iter()
         .collect();
 
-        let dynamic_b: HashMap<String, DocumentValue> = vec![
+        let dynamic_b: ValueMap = vec![
             (
                 String::from("property_b"),
                 DocumentValue::String(String::from("value_b")),
@@ -0,0 +1,251 @@
+/// Constructs [`DocumentValue`](crate::types::DocumentValue) with JSON-like syntax.
+///
+/// Besides the JSON literals, the leaves can be typed binary values and versions:
+/// `id(..)` (anything convertible into `Identifier`), `bytes(..)`, `static_bytes(..)` and
+/// `version(..)`. Any other expression is converted with `Into<DocumentValue>`. Keys are string
+/// literals or expressions in parentheses.
+///
+/// Each element or value which isn't a literal, an array, a map or a call like `id(..)` takes
+/// a level of macro recursion, so in large values such expressions should be put in
+/// parentheses, e.g. `(a + 1)`, to stay within the `recursion_limit`.
+///
+/// ```
+/// use oxygen::{types::DocumentValue, value};
+///
+/// let owner = [1_u8; 32];
+/// let value = value!({
+///     "$ownerId": id(owner),
+///     "$protocolVersion": version(1),
+///     "name": "Cutie",
+///     "tags": ["cat", null],
+///     "avatar": bytes(vec![1, 2, 3]),
+///     "age": 2 + 1,
+/// });
+///
+/// assert_eq!(DocumentValue::Integer(3), value["age"]);
+/// ```
+#[macro_export]
+macro_rules! value {
+    // map entries. A value is either a token tree followed by an optional group or literal,
+    // which covers the literals, `null`, arrays, maps, `-1` and `id(..)`, or any other
+    // expression. The former are taken eight at a time, to keep the recursion shallow
+    (@map $map:ident) => {};
+    (@map $map:ident
+        $k1:tt : $v1:tt $(($($va1:tt)*))? $($vl1:literal)?,
+        $k2:tt : $v2:tt $(($($va2:tt)*))? $($vl2:literal)?,
+        $k3:tt : $v3:tt $(($($va3:tt)*))? $($vl3:literal)?,
+        $k4:tt : $v4:tt $(($($va4:tt)*))? $($vl4:literal)?,
+        $k5:tt : $v5:tt $(($($va5:tt)*))? $($vl5:literal)?,
+        $k6:tt : $v6:tt $(($($va6:tt)*))? $($vl6:literal)?,
+        $k7:tt : $v7:tt $(($($va7:tt)*))? $($vl7:literal)?,
+        $k8:tt : $v8:tt $(($($va8:tt)*))? $($vl8:literal)?,
+        $($rest:tt)*
+    ) => {
+        $map.insert($crate::value!(@key $k1), $crate::value!($v1 $(($($va1)*))? $($vl1)?));
+        $map.insert($crate::value!(@key $k2), $crate::value!($v2 $(($($va2)*))? $($vl2)?));
+        $map.insert($crate::value!(@key $k3), $crate::value!($v3 $(($($va3)*))? $($vl3)?));
+        $map.insert($crate::value!(@key $k4), $crate::value!($v4 $(($($va4)*))? $($vl4)?));
+        $map.insert($crate::value!(@key $k5), $crate::value!($v5 $(($($va5)*))? $($vl5)?));
+        $map.insert($crate::value!(@key $k6), $crate::value!($v6 $(($($va6)*))? $($vl6)?));
+        $map.insert($crate::value!(@key $k7), $crate::value!($v7 $(($($va7)*))? $($vl7)?));
+        $map.insert($crate::value!(@key $k8), $crate::value!($v8 $(($($va8)*))? $($vl8)?));
+        $crate::value!(@map $map $($rest)*);
+    };
+    (@map $map:ident
+        $key:tt : $value:tt $(($($args:tt)*))? $($literal:literal)? $(, $($rest:tt)*)?
+    ) => {
+        $map.insert(
+            $crate::value!(@key $key),
+            $crate::value!($value $(($($args)*))? $($literal)?),
+        );
+        $crate::value!(@map $map $($($rest)*)?);
+    };
+    (@map $map:ident $key:tt : $value:expr $(, $($rest:tt)*)?) => {
+        $map.insert($crate::value!(@key $key), $crate::value!($value));
+        $crate::value!(@map $map $($($rest)*)?);
+    };
+    (@key ($key:expr)) => {
+        $key
+    };
+    (@key $key:literal) => {
+        $key
+    };
+
+    // array elements, the same way as map values
+    (@array [$($done:expr,)*]) => {
+        ::std::vec![$($done,)*]
+    };
+    (@array [$($done:expr,)*]
+        $e1:tt $(($($ea1:tt)*))? $($el1:literal)?,
+        $e2:tt $(($($ea2:tt)*))? $($el2:literal)?,
+        $e3:tt $(($($ea3:tt)*))? $($el3:literal)?,
+        $e4:tt $(($($ea4:tt)*))? $($el4:literal)?,
+        $e5:tt $(($($ea5:tt)*))? $($el5:literal)?,
+        $e6:tt $(($($ea6:tt)*))? $($el6:literal)?,
+        $e7:tt $(($($ea7:tt)*))? $($el7:literal)?,
+        $e8:tt $(($($ea8:tt)*))? $($el8:literal)?,
+        $($rest:tt)*
+    ) => {
+        $crate::value!(@array [
+            $($done,)*
+            $crate::value!($e1 $(($($ea1)*))? $($el1)?),
+            $crate::value!($e2 $(($($ea2)*))? $($el2)?),
+            $crate::value!($e3 $(($($ea3)*))? $($el3)?),
+            $crate::value!($e4 $(($($ea4)*))? $($el4)?),
+            $crate::value!($e5 $(($($ea5)*))? $($el5)?),
+            $crate::value!($e6 $(($($ea6)*))? $($el6)?),
+            $crate::value!($e7 $(($($ea7)*))? $($el7)?),
+            $crate::value!($e8 $(($($ea8)*))? $($el8)?),
+        ] $($rest)*)
+    };
+    (@array [$($done:expr,)*]
+        $element:tt $(($($args:tt)*))? $($literal:literal)? $(, $($rest:tt)*)?
+    ) => {
+        $crate::value!(@array [
+            $($done,)*
+            $crate::value!($element $(($($args)*))? $($literal)?),
+        ] $($($rest)*)?)
+    };
+    (@array [$($done:expr,)*] $element:expr $(, $($rest:tt)*)?) => {
+        $crate::value!(@array [$($done,)* $crate::value!($element),] $($($rest)*)?)
+    };
+
+    (null) => {
+        $crate::types::DocumentValue::Null
+    };
+    ([ $($elements:tt)* ]) => {
+        $crate::types::DocumentValue::Array($crate::value!(@array [] $($elements)*))
+    };
+    ({ $($entries:tt)* }) => {{
+        #[allow(unused_mut)]
+        let mut map = $crate::types::ValueMap::new();
+        $crate::value!(@map map $($entries)*);
+        $crate::types::DocumentValue::Map(map)
+    }};
+    (id($($id:tt)+)) => {
+        $crate::types::DocumentValue::Identifier($crate::types::Identifier::from($($id)+))
+    };
+    (bytes($($bytes:tt)+)) => {
+        $crate::types::DocumentValue::Bytes($crate::types::Bytes::from($($bytes)+))
+    };
+    (static_bytes($($bytes:tt)+)) => {
+        $crate::types::DocumentValue::StaticBytes($crate::types::StaticBytes::from($($bytes)+))
+    };
+    (version($($version:tt)+)) => {
+        $crate::types::DocumentValue::Version($($version)+)
+    };
+    ($other:expr) => {
+        $crate::types::DocumentValue::from($other)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::{Bytes, DocumentValue, Identifier, StaticBytes, ValueMap};
+
+    #[test]
+    fn literals() {
+        assert_eq!(DocumentValue::Null, value!(null));
+        assert_eq!(DocumentValue::Bool(true), value!(true));
+        assert_eq!(DocumentValue::Integer(-5), value!(-5));
+        assert_eq!(DocumentValue::Float(1.5), value!(1.5));
+        assert_eq!(DocumentValue::String(String::from("a")), value!("a"));
+        assert_eq!(DocumentValue::Array(vec![]), value!([]));
+        assert_eq!(DocumentValue::Map(ValueMap::new()), value!({}));
+    }
+
+    #[test]
+    fn typed_leaves() {
+        assert_eq!(
+            DocumentValue::Identifier(Identifier::from([1_u8; 32])),
+            value!(id([1_u8; 32]))
+        );
+        assert_eq!(
+            DocumentValue::Bytes(Bytes(vec![1, 2])),
+            value!(bytes(vec![1, 2]))
+        );
+        assert_eq!(
+            DocumentValue::StaticBytes(StaticBytes([3_u8; 32])),
+            value!(static_bytes([3_u8; 32]))
+        );
+        assert_eq!(DocumentValue::Version(1), value!(version(1)));
+    }
+
+    #[test]
+    fn nested() {
+        let name = String::from("Cutie");
+        let owner = Identifier::from([1_u8; 32]);
+
+        let value = value!({
+            "$ownerId": id(owner),
+            "name": name.clone(),
+            "age": 1 + 2,
+            "tags": ["cat", null, [1, -1], { "a": bytes([1_u8, 2]) },],
+            ("dyn".to_owned() + "amic"): { "nested": { "deep": true } },
+            "nothing": Option::<u8>::None,
+        });
+
+        let mut nested = ValueMap::new();
+        nested.insert("deep", DocumentValue::Bool(true));
+        let mut dynamic = ValueMap::new();
+        dynamic.insert("nested", DocumentValue::Map(nested));
+        let mut inner = ValueMap::new();
+        inner.insert("a", DocumentValue::Bytes(Bytes(vec![1, 2])));
+
+        let mut expected = ValueMap::new();
+        expected.insert("$ownerId", DocumentValue::Identifier(owner));
+        expected.insert("name", DocumentValue::String(name));
+        expected.insert("age", DocumentValue::Integer(3));
+        expected.insert(
+            "tags",
+            DocumentValue::Array(vec![
+                DocumentValue::String(String::from("cat")),
+                DocumentValue::Null,
+                DocumentValue::Array(vec![DocumentValue::Integer(1), DocumentValue::Integer(-1)]),
+                DocumentValue::Map(inner),
+            ]),
+        );
+        expected.insert("dynamic", DocumentValue::Map(dynamic));
+        expected.insert("nothing", DocumentValue::Null);
+
+        assert_eq!(DocumentValue::Map(expected), value);
+    }
+
+    #[test]
+    fn many_entries() {
+        // doubles the tokens once per `x` in the brackets and passes them to `value!`
+        macro_rules! doubled {
+            ([] array $($tokens:tt)*) => {
+                value!([$($tokens)*])
+            };
+            ([] map $($tokens:tt)*) => {
+                value!({ $($tokens)* })
+            };
+            ([x $($x:tt)*] $kind:ident $($tokens:tt)*) => {
+                doubled!([$($x)*] $kind $($tokens)* $($tokens)*)
+            };
+        }
+
+        let id = Identifier::from([1_u8; 32]);
+        let array = doubled!([x x x x x x x] array -1, id(id), bytes([2_u8]), (1 + 2),);
+        let expected = [
+            DocumentValue::Integer(-1),
+            DocumentValue::Identifier(id),
+            DocumentValue::Bytes(Bytes(vec![2])),
+            DocumentValue::Integer(3),
+        ];
+        let expected = expected.iter().cycle().take(512).cloned().collect();
+        assert_eq!(DocumentValue::Array(expected), array);
+
+        let mut count = 0;
+        let mut key = || {
+            count += 1;
+            format!("k{count}")
+        };
+        let map = doubled!([x x x x x x x x] map (key()): id(id),);
+        let expected = (1..=256)
+            .map(|i| (format!("k{i}"), DocumentValue::Identifier(id)))
+            .collect();
+        assert_eq!(DocumentValue::Map(expected), map);
+    }
+}
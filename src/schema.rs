@@ -0,0 +1,320 @@
+//! Conversions guided by a data contract document schema.
+//!
+//! JSON has no binary types, so identifiers and byte arrays arrive as strings or arrays of
+//! integers. The schema tells where binary data is expected: properties with
+//! `byteArray: true` become [`DocumentValue::Bytes`], or [`DocumentValue::StaticBytes`] when
+//! the length is fixed to 32 bytes, and the ones with the identifier `contentMediaType`
+//! become [`DocumentValue::Identifier`]. The system properties `$id`, `$ownerId` and
+//! `$dataContractId` are always identifiers.
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::error::Error;
+use crate::serializer::ToDashValue;
+use crate::types::{BinaryKind, DocumentValue, Path, PathSegment, STATIC_BYTES_LENGTH};
+
+pub const IDENTIFIER_MEDIA_TYPE: &str = "application/x.dash.dpp.identifier";
+
+const SYSTEM_IDENTIFIERS: [&str; 3] = ["$id", "$ownerId", "$dataContractId"];
+
+impl BinaryKind {
+    /// Returns the binary type described by the property schema
+    pub fn from_schema(schema: &JsonValue) -> Option<BinaryKind> {
+        if schema.get("byteArray") != Some(&JsonValue::Bool(true)) {
+            return None;
+        }
+        if schema.get("contentMediaType").and_then(JsonValue::as_str) == Some(IDENTIFIER_MEDIA_TYPE)
+        {
+            return Some(BinaryKind::Identifier);
+        }
+        let min_items = schema.get("minItems").and_then(JsonValue::as_u64);
+        let max_items = schema.get("maxItems").and_then(JsonValue::as_u64);
+        if min_items == Some(STATIC_BYTES_LENGTH as u64) && max_items == min_items {
+            return Some(BinaryKind::StaticBytes);
+        }
+        Some(BinaryKind::Bytes)
+    }
+}
+
+/// Walks the value along the schema and converts the binary properties
+pub(crate) fn apply_schema(
+    value: &mut DocumentValue,
+    schema: &JsonValue,
+    root: &JsonValue,
+    accept_strings: bool,
+    path: &mut Path,
+) -> Result<(), Error> {
+    let schema = resolve_ref(schema, root, path)?;
+
+    if let Some(kind) = BinaryKind::from_schema(schema) {
+        let owned = std::mem::take(value);
+        *value = kind.convert(owned, accept_strings, path)?;
+        return Ok(());
+    }
+
+    match value {
+        DocumentValue::Map(map) => {
+            if let Some(JsonValue::Object(properties)) = schema.get("properties") {
+                for (key, property_schema) in properties {
+                    if let Some(property) = map.get_mut(key) {
+                        path.push(PathSegment::Key(key.clone()));
+                        apply_schema(property, property_schema, root, accept_strings, path)?;
+                        path.pop();
+                    }
+                }
+            }
+        }
+        DocumentValue::Array(array) => match schema.get("items") {
+            Some(JsonValue::Array(items_schemas)) => {
+                for (i, (item, item_schema)) in array.iter_mut().zip(items_schemas).enumerate() {
+                    path.push(PathSegment::Index(i));
+                    apply_schema(item, item_schema, root, accept_strings, path)?;
+                    path.pop();
+                }
+            }
+            Some(items_schema) => {
+                for (i, item) in array.iter_mut().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    apply_schema(item, items_schema, root, accept_strings, path)?;
+                    path.pop();
+                }
+            }
+            None => {}
+        },
+        _ => {}
+    }
+    Ok(())
+}
+
+// Follows local references, e.g. `#/$defs/address`
+fn resolve_ref<'a>(
+    schema: &'a JsonValue,
+    root: &'a JsonValue,
+    path: &Path,
+) -> Result<&'a JsonValue, Error> {
+    let mut schema = schema;
+    // guards against reference cycles
+    for _ in 0..32 {
+        let reference = match schema.get("$ref").and_then(JsonValue::as_str) {
+            Some(reference) => reference,
+            None => return Ok(schema),
+        };
+        schema = reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+            .ok_or_else(|| path.error(format!("unresolvable schema reference '{reference}'")))?;
+    }
+    Err(path.error("too many nested schema references"))
+}
+
+impl DocumentValue {
+    /// Converts a JSON document into [`DocumentValue`], restoring binary types according
+    /// to the document schema
+    pub fn from_json_with_schema(
+        json: JsonValue,
+        schema: &JsonValue,
+    ) -> Result<DocumentValue, Error> {
+        let mut value = json.serialize(ToDashValue::default())?;
+        value.apply_schema(schema)?;
+        Ok(value)
+    }
+
+    /// Converts the value into JSON. Binary properties described by the schema are written as
+    /// base58 (identifiers) or base64 (bytes) strings, whatever variant currently holds them
+    pub fn to_json_with_schema(&self, schema: &JsonValue) -> Result<JsonValue, Error> {
+        let mut value = self.clone();
+        value.apply_schema(schema)?;
+        serde_json::to_value(&value).map_err(|e| Error::SerializationError(e.to_string()))
+    }
+
+    /// Converts binary properties described by the schema, in place, into `Identifier`,
+    /// `Bytes` or `StaticBytes`. Strings, integer arrays and other binary variants are accepted
+    pub fn apply_schema(&mut self, schema: &JsonValue) -> Result<(), Error> {
+        let mut path = Path::root();
+        apply_schema(self, schema, schema, true, &mut path)?;
+        self.apply_system_identifiers(true)
+    }
+
+    /// The inverse of [`DocumentValue::bytes_as_arrays`] driven by the document schema.
+    /// Integer arrays under binary properties are converted into `Identifier`, `Bytes` or
+    /// `StaticBytes`; strings are rejected
+    pub fn arrays_as_bytes_with_schema(
+        mut self,
+        schema: &JsonValue,
+    ) -> Result<DocumentValue, Error> {
+        let mut path = Path::root();
+        apply_schema(&mut self, schema, schema, false, &mut path)?;
+        self.apply_system_identifiers(false)?;
+        Ok(self)
+    }
+
+    pub(crate) fn apply_system_identifiers(&mut self, accept_strings: bool) -> Result<(), Error> {
+        if let DocumentValue::Map(map) = self {
+            for key in SYSTEM_IDENTIFIERS {
+                if let Some(value) = map.get_mut(key) {
+                    let path = Path::from(vec![PathSegment::Key(String::from(key))]);
+                    let owned = std::mem::take(value);
+                    *value = BinaryKind::Identifier.convert(owned, accept_strings, &path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{Bytes, Identifier, StaticBytes};
+
+    fn schema() -> JsonValue {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "avatar": { "type": "array", "byteArray": true, "maxItems": 1024 },
+                "hash": { "type": "array", "byteArray": true, "minItems": 32, "maxItems": 32 },
+                "friend": {
+                    "type": "array",
+                    "byteArray": true,
+                    "minItems": 32,
+                    "maxItems": 32,
+                    "contentMediaType": "application/x.dash.dpp.identifier"
+                },
+                "address": { "$ref": "#/$defs/address" },
+                "keys": {
+                    "type": "array",
+                    "items": { "type": "array", "byteArray": true }
+                }
+            },
+            "$defs": {
+                "address": {
+                    "type": "object",
+                    "properties": {
+                        "owner": {
+                            "type": "array",
+                            "byteArray": true,
+                            "contentMediaType": "application/x.dash.dpp.identifier"
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn restores_binary_types_from_json() {
+        let id = bs58::encode([1_u8; 32]).into_string();
+        let json = json!({
+            "$id": id,
+            "$ownerId": vec![2_u8; 32],
+            "name": "alpha",
+            "avatar": base64::encode([3_u8, 4, 5]),
+            "hash": vec![6_u8; 32],
+            "friend": id,
+            "address": { "owner": id },
+            "keys": [[7, 8], base64::encode([9_u8])]
+        });
+
+        let value = DocumentValue::from_json_with_schema(json, &schema()).expect("no error");
+
+        assert_eq!(
+            DocumentValue::Identifier(Identifier::from([1_u8; 32])),
+            value["$id"]
+        );
+        assert_eq!(
+            DocumentValue::Identifier(Identifier::from([2_u8; 32])),
+            value["$ownerId"]
+        );
+        assert_eq!(DocumentValue::String(String::from("alpha")), value["name"]);
+        assert_eq!(DocumentValue::Bytes(Bytes(vec![3, 4, 5])), value["avatar"]);
+        assert_eq!(
+            DocumentValue::StaticBytes(StaticBytes([6_u8; 32])),
+            value["hash"]
+        );
+        assert_eq!(
+            DocumentValue::Identifier(Identifier::from([1_u8; 32])),
+            value["friend"]
+        );
+        assert_eq!(
+            DocumentValue::Identifier(Identifier::from([1_u8; 32])),
+            value["address"]["owner"]
+        );
+        assert_eq!(DocumentValue::Bytes(Bytes(vec![7, 8])), value["keys"][0]);
+        assert_eq!(DocumentValue::Bytes(Bytes(vec![9])), value["keys"][1]);
+    }
+
+    #[test]
+    fn writes_binary_types_as_strings() {
+        let json = json!({
+            "$id": vec![1_u8; 32],
+            "friend": vec![1_u8; 32],
+            "avatar": [3, 4, 5],
+        });
+        let value: DocumentValue = json.try_into().expect("no error");
+
+        let output = value.to_json_with_schema(&schema()).expect("no error");
+        let id = bs58::encode([1_u8; 32]).into_string();
+        assert_eq!(
+            json!({
+                "$id": id,
+                "friend": id,
+                "avatar": base64::encode([3_u8, 4, 5]),
+            }),
+            output
+        );
+    }
+
+    #[test]
+    fn errors_point_to_property() {
+        let json = json!({ "keys": [[1, 2], [3, 256]] });
+        let err = DocumentValue::from_json_with_schema(json, &schema()).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::PathError { ref path, ref segment, .. } if path == "keys[1][1]" && segment == "[1]"
+        ));
+
+        let json = json!({ "friend": [1, 2, 3] });
+        let err = DocumentValue::from_json_with_schema(json, &schema()).unwrap_err();
+        assert!(matches!(err, Error::PathError { ref path, .. } if path == "friend"));
+
+        let json = json!({ "friend": "not base58 0OIl" });
+        assert!(DocumentValue::from_json_with_schema(json, &schema()).is_err());
+    }
+
+    #[test]
+    fn arrays_as_bytes_with_schema() {
+        let original: DocumentValue = json!({
+            "$id": vec![1_u8; 32],
+            "avatar": [3, 4, 5],
+            "hash": vec![6_u8; 32],
+            "friend": vec![1_u8; 32],
+            "name": "alpha",
+        })
+        .try_into()
+        .expect("no error");
+
+        let typed = original
+            .arrays_as_bytes_with_schema(&schema())
+            .expect("no error");
+        assert!(matches!(typed["$id"], DocumentValue::Identifier(_)));
+        assert!(matches!(typed["avatar"], DocumentValue::Bytes(_)));
+        assert!(matches!(typed["hash"], DocumentValue::StaticBytes(_)));
+        assert!(matches!(typed["friend"], DocumentValue::Identifier(_)));
+
+        let round_trip = typed
+            .clone()
+            .bytes_as_arrays()
+            .arrays_as_bytes_with_schema(&schema())
+            .expect("no error");
+        assert_eq!(typed, round_trip);
+
+        let with_string: DocumentValue = json!({ "avatar": "AQID" }).try_into().expect("no error");
+        let err = with_string
+            .arrays_as_bytes_with_schema(&schema())
+            .unwrap_err();
+        assert!(matches!(err, Error::PathError { ref path, .. } if path == "avatar"));
+    }
+}
@@ -1,26 +1,24 @@
 type Result<K> = std::result::Result<K, Error>;
-use std::collections::HashMap;
-
 use serde::Serialize;
 
 use super::to_string::ToStringSerializer;
 use super::to_value::ToDashValue;
 
 use crate::error::Error;
-use crate::types::DocumentValue as Value;
+use crate::types::{DocumentValue as Value, ValueMap};
 
-#[derive(Default)]
 pub struct SerializeMap {
-    skip_version: bool,
-    map: HashMap<String, Value>,
+    serializer: ToDashValue,
+    map: ValueMap,
     next_key: Option<String>,
 }
 
 impl SerializeMap {
-    pub fn new(ignore_version: bool) -> Self {
+    pub fn new(serializer: ToDashValue) -> Self {
         Self {
-            skip_version: ignore_version,
-            ..Default::default()
+            serializer,
+            map: ValueMap::new(),
+            next_key: None,
         }
     }
 }
@@ -29,25 +27,25 @@ impl serde::ser::SerializeMap for SerializeMap {
     type Error = Error;
     type Ok = Value;
 
-    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<()>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         self.next_key = Some(key.serialize(ToStringSerializer)?);
         Ok(())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<()>
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         let key = self.next_key.take();
         // Panic because this indicates a bug in the program rather than an
         // expected failure.
         let key = key.expect("serialize_value called before serialize_key");
-        let new_value = value.serialize(ToDashValue::default())?;
+        let new_value = value.serialize(self.serializer)?;
 
-        if matches!(new_value, Value::Version(_)) && self.skip_version {
+        if matches!(new_value, Value::Version(_)) && self.serializer.skip_version {
             return Ok(());
         }
 
@@ -63,9 +61,9 @@ impl serde::ser::SerializeMap for SerializeMap {
 impl serde::ser::SerializeStruct for SerializeMap {
     type Error = Error;
     type Ok = Value;
-    fn serialize_field<T: ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         serde::ser::SerializeMap::serialize_entry(self, key, value)
     }
@@ -74,3 +72,35 @@ impl serde::ser::SerializeStruct for SerializeMap {
         Ok(Value::Map(self.map))
     }
 }
+
+pub struct SerializeStructVariant {
+    name: String,
+    map: SerializeMap,
+}
+
+impl SerializeStructVariant {
+    pub fn new(name: String, serializer: ToDashValue) -> Self {
+        Self {
+            name,
+            map: SerializeMap::new(serializer),
+        }
+    }
+}
+
+impl serde::ser::SerializeStructVariant for SerializeStructVariant {
+    type Error = Error;
+    type Ok = Value;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        serde::ser::SerializeMap::serialize_entry(&mut self.map, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        let mut map = ValueMap::with_capacity(1);
+        map.insert(self.name, Value::Map(self.map.map));
+        Ok(Value::Map(map))
+    }
+}
@@ -3,5 +3,7 @@ mod to_string;
 mod to_value;
 mod unsupported;
 mod vec;
+mod with_encodings;
 
 pub use to_value::ToDashValue;
+pub use with_encodings::WithEncodings;
@@ -63,6 +63,10 @@ impl serde::Serializer for ToStringSerializer {
         Ok(value.to_string())
     }
 
+    fn serialize_i128(self, value: i128) -> Result<String> {
+        Ok(value.to_string())
+    }
+
     fn serialize_u8(self, value: u8) -> Result<String> {
         Ok(value.to_string())
     }
@@ -79,6 +83,10 @@ impl serde::Serializer for ToStringSerializer {
         Ok(value.to_string())
     }
 
+    fn serialize_u128(self, value: u128) -> Result<String> {
+        Ok(value.to_string())
+    }
+
     fn serialize_f32(self, _value: f32) -> Result<String> {
         Err(key_must_be_a_string())
     }
@@ -138,11 +146,11 @@ impl serde::Serializer for ToStringSerializer {
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
-        Err(key_must_be_a_string())
+        Ok(Unsupported::new("sequence as a map key"))
     }
 
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
-        Err(key_must_be_a_string())
+        Ok(Unsupported::new("tuple as a map key"))
     }
 
     fn serialize_tuple_struct(
@@ -150,7 +158,7 @@ impl serde::Serializer for ToStringSerializer {
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        Err(key_must_be_a_string())
+        Ok(Unsupported::new("tuple struct as a map key"))
     }
 
     fn serialize_tuple_variant(
@@ -160,15 +168,15 @@ impl serde::Serializer for ToStringSerializer {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        Err(key_must_be_a_string())
+        Ok(Unsupported::new("tuple variant as a map key"))
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        Err(key_must_be_a_string())
+        Ok(Unsupported::new("map as a map key"))
     }
 
     fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
-        Err(key_must_be_a_string())
+        Ok(Unsupported::new("struct as a map key"))
     }
 
     fn serialize_struct_variant(
@@ -178,7 +186,7 @@ impl serde::Serializer for ToStringSerializer {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        Err(key_must_be_a_string())
+        Ok(Unsupported::new("struct variant as a map key"))
     }
 
     fn collect_str<T>(self, value: &T) -> Result<String>
@@ -188,3 +196,24 @@ impl serde::Serializer for ToStringSerializer {
         Ok(value.to_string())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+
+    use crate::error::Error;
+    use crate::serializer::ToDashValue;
+
+    #[test]
+    fn compound_keys_are_unsupported() {
+        let mut map = BTreeMap::new();
+        map.insert(vec![1_u8], 1);
+
+        let result = map.serialize(ToDashValue::default());
+        assert!(
+            matches!(result, Err(Error::Unsupported(reason)) if reason == "sequence as a map key")
+        );
+    }
+}
@@ -2,22 +2,53 @@ use serde::Serialize;
 use std::fmt::Display;
 
 use crate::error::Error;
-use crate::types::{DocumentValue as Value, Identifier, Version};
+use crate::types::{DocumentValue as Value, Encodings, Identifier, StaticBytes, ValueMap};
 
-use super::{map::SerializeMap, unsupported::Unsupported, vec::SerializeVec};
+use super::{
+    map::{SerializeMap, SerializeStructVariant},
+    vec::{SerializeTupleVariant, SerializeVec},
+};
 
 type Result<K> = std::result::Result<K, Error>;
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone, Copy)]
 pub struct ToDashValue {
-    skip_version: bool,
+    pub(super) skip_version: bool,
+    human_readable: bool,
+    encodings: Option<Encodings>,
 }
 
 impl ToDashValue {
+    /// Human-readable mode: identifiers become base58 and bytes become base64 strings,
+    /// the same way they are represented in JSON. The value is read back with
+    /// [`FromDashValue::human_readable`](crate::deserializer::FromDashValue::human_readable)
+    pub fn human_readable() -> Self {
+        Self {
+            human_readable: true,
+            encodings: Some(Encodings::default()),
+            ..Default::default()
+        }
+    }
+
     pub fn with_skip_version(mut self, ignore_version: bool) -> Self {
         self.skip_version = ignore_version;
         self
     }
+
+    /// Identifiers and bytes are converted into `Value::String` with given encodings
+    pub fn with_encodings(mut self, encodings: Encodings) -> Self {
+        self.encodings = Some(encodings);
+        self
+    }
+
+    // serializer producing the binary variants, used to get the raw data of the special types
+    fn binary(self) -> Self {
+        Self {
+            human_readable: false,
+            encodings: None,
+            ..self
+        }
+    }
 }
 
 // Serializer whose output is a `Value`
@@ -27,11 +58,11 @@ impl serde::Serializer for ToDashValue {
 
     type SerializeSeq = SerializeVec;
     type SerializeTuple = SerializeVec;
-    type SerializeTupleStruct = Unsupported<Value>;
-    type SerializeTupleVariant = Unsupported<Value>;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeTupleVariant;
     type SerializeMap = SerializeMap;
     type SerializeStruct = SerializeMap;
-    type SerializeStructVariant = Unsupported<Value>;
+    type SerializeStructVariant = SerializeStructVariant;
 
     #[inline]
     fn serialize_bool(self, value: bool) -> Result<Value> {
@@ -57,6 +88,10 @@ impl serde::Serializer for ToDashValue {
         Ok(Value::Integer(value))
     }
 
+    fn serialize_i128(self, value: i128) -> Result<Value> {
+        Ok(Value::from_i128(value))
+    }
+
     #[inline]
     fn serialize_u8(self, value: u8) -> Result<Value> {
         self.serialize_u64(value as u64)
@@ -77,6 +112,10 @@ impl serde::Serializer for ToDashValue {
         Ok(Value::UInteger(value))
     }
 
+    fn serialize_u128(self, value: u128) -> Result<Value> {
+        Ok(Value::from_u128(value))
+    }
+
     #[inline]
     fn serialize_f32(self, value: f32) -> Result<Value> {
         self.serialize_f64(value as f64)
@@ -101,7 +140,10 @@ impl serde::Serializer for ToDashValue {
 
     // ? how to avoid the another allocation?
     fn serialize_bytes(self, value: &[u8]) -> Result<Value> {
-        Ok(Value::Bytes(value.to_owned().into()))
+        match self.encodings {
+            Some(encodings) => Ok(Value::String(encodings.bytes.encode(value))),
+            None => Ok(Value::Bytes(value.to_owned().into())),
+        }
     }
 
     #[inline]
@@ -129,60 +171,60 @@ impl serde::Serializer for ToDashValue {
     where
         T: ?Sized + Serialize,
     {
-        if name == "Version" {
-            match value.serialize(self)? {
-                Value::UInteger(u) => {
-                    return Ok(Value::Version(u as u32));
-                }
-                data => {
-                    panic!("expected Value::Bytes, got: {data:#?}")
-                }
-            }
-        }
-        if name == "StaticBytes" {
-            match value.serialize(self)? {
-                Value::StaticBytes(b) => {
-                    return Ok(Value::StaticBytes(b));
-                }
-                data => {
-                    panic!("expected Value::StaticBytes, got: {data:#?}")
-                }
-            }
-        }
-        if name == "Bytes" {
-            match value.serialize(self)? {
-                Value::Bytes(b) => {
-                    return Ok(Value::Bytes(b));
-                }
-                data => {
-                    panic!("expected Value::Bytes, got: {data:#?}")
+        match name {
+            "Version" => match value.serialize(self)? {
+                Value::UInteger(u) => u32::try_from(u)
+                    .map(Value::Version)
+                    .map_err(|_| Error::unexpected_type("Version", "UInteger out of u32 range")),
+                data => Err(Error::unexpected_type("UInteger", data.variant_name())),
+            },
+            "StaticBytes" => {
+                let data = match value.serialize(self.binary())? {
+                    Value::StaticBytes(b) => b.0.to_vec(),
+                    Value::Bytes(b) => b.0,
+                    data => return Err(Error::unexpected_type("Bytes", data.variant_name())),
+                };
+                match (self.encodings, <[u8; 32]>::try_from(data.as_slice())) {
+                    (Some(encodings), _) => Ok(Value::String(encodings.bytes.encode(data))),
+                    (None, Ok(array)) => Ok(Value::StaticBytes(StaticBytes(array))),
+                    (None, Err(_)) => Ok(Value::Bytes(data.into())),
                 }
             }
-        }
-        if name == "identifier" {
-            match value.serialize(self)? {
-                Value::Bytes(b) => {
-                    return Ok(Value::Identifier(Identifier { data: b.0 }));
-                }
-                data => {
-                    panic!("expected Value::Bytes, got: {data:#?}")
+            "Bytes" => match value.serialize(self.binary())? {
+                Value::Bytes(b) => match self.encodings {
+                    Some(encodings) => Ok(Value::String(encodings.bytes.encode(b.0))),
+                    None => Ok(Value::Bytes(b)),
+                },
+                data => Err(Error::unexpected_type("Bytes", data.variant_name())),
+            },
+            "identifier" => {
+                let id = match value.serialize(self.binary())? {
+                    Value::Bytes(b) => Identifier::try_from(b.0)?,
+                    data => return Err(Error::unexpected_type("Bytes", data.variant_name())),
+                };
+                match self.encodings {
+                    Some(encodings) => Ok(Value::String(encodings.identifier.encode(id.data))),
+                    None => Ok(Value::Identifier(id)),
                 }
             }
+            _ => value.serialize(self),
         }
-        value.serialize(self)
     }
 
+    // enums with data use the externally tagged representation: `{ variant: value }`
     fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _value: &T,
+        variant: &'static str,
+        value: &T,
     ) -> Result<Value>
     where
         T: ?Sized + Serialize,
     {
-        Err(Error::unsupported("new type variant"))
+        let mut map = ValueMap::with_capacity(1);
+        map.insert(variant, value.serialize(self)?);
+        Ok(Value::Map(map))
     }
 
     #[inline]
@@ -201,6 +243,7 @@ impl serde::Serializer for ToDashValue {
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
         Ok(SerializeVec {
             vec: Vec::with_capacity(len.unwrap_or(0)),
+            serializer: self,
         })
     }
 
@@ -211,23 +254,27 @@ impl serde::Serializer for ToDashValue {
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        Err(Error::unsupported("tuple struct isn't supported yet"))
+        self.serialize_seq(Some(len))
     }
 
     fn serialize_tuple_variant(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
+        variant: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        Err(Error::unsupported("tuple variant isn't supported yet"))
+        Ok(SerializeTupleVariant {
+            name: String::from(variant),
+            vec: Vec::with_capacity(len),
+            serializer: self,
+        })
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        Ok(SerializeMap::new(self.skip_version))
+        Ok(SerializeMap::new(self))
     }
 
     fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
@@ -238,10 +285,10 @@ impl serde::Serializer for ToDashValue {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        Err(Error::unsupported("struct variant is not supported"))
+        Ok(SerializeStructVariant::new(String::from(variant), self))
     }
 
     fn collect_str<T>(self, value: &T) -> Result<Value>
@@ -252,7 +299,7 @@ impl serde::Serializer for ToDashValue {
     }
 
     fn is_human_readable(&self) -> bool {
-        false
+        self.human_readable
     }
 }
 
@@ -261,6 +308,7 @@ mod test {
     use serde::Deserialize;
 
     use super::*;
+    use crate::types::Version;
 
     #[test]
     fn skip_version() {
@@ -291,4 +339,357 @@ mod test {
 
         assert_eq!(Some(&Value::Version(0)), serialized.get("version"))
     }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Transition {
+        Delete,
+        Create(Identifier),
+        Move(i64, i64),
+        Replace { revision: u32, version: Version },
+    }
+
+    fn single_entry(value: &Value) -> (&String, &Value) {
+        match value {
+            Value::Map(map) if map.len() == 1 => map.iter().next().unwrap(),
+            other => panic!("expected map with a single entry, got: {other:#?}"),
+        }
+    }
+
+    #[test]
+    fn externally_tagged_enums() {
+        let unit = Transition::Delete
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        assert_eq!(Value::String(String::from("Delete")), unit);
+
+        let newtype = Transition::Create(Identifier::from([1_u8; 32]))
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        let (variant, value) = single_entry(&newtype);
+        assert_eq!("Create", variant);
+        assert!(matches!(value, Value::Identifier(_)));
+
+        let tuple = Transition::Move(-1, 2)
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        let (variant, value) = single_entry(&tuple);
+        assert_eq!("Move", variant);
+        assert_eq!(
+            &Value::Array(vec![Value::Integer(-1), Value::Integer(2)]),
+            value
+        );
+
+        let structure = Transition::Replace {
+            revision: 2,
+            version: Version(1),
+        }
+        .serialize(ToDashValue::default())
+        .expect("no errors");
+        let (variant, value) = single_entry(&structure);
+        assert_eq!("Replace", variant);
+        assert_eq!(Some(&Value::UInteger(2)), value.get("revision"));
+        assert_eq!(Some(&Value::Version(1)), value.get("version"));
+    }
+
+    #[test]
+    fn struct_variant_skip_version() {
+        let structure = Transition::Replace {
+            revision: 2,
+            version: Version(1),
+        }
+        .serialize(ToDashValue::default().with_skip_version(true))
+        .expect("no errors");
+
+        let (_, value) = single_entry(&structure);
+        assert!(value.get("version").is_none());
+    }
+
+    #[test]
+    fn enums_round_trip() {
+        for transition in [
+            Transition::Delete,
+            Transition::Create(Identifier::from([1_u8; 32])),
+            Transition::Move(-1, 2),
+            Transition::Replace {
+                revision: 2,
+                version: Version(1),
+            },
+        ] {
+            let value = transition
+                .serialize(ToDashValue::default())
+                .expect("no errors");
+            let deserialized: Transition =
+                crate::deserializer::from_dash_value(value).expect("no errors");
+            assert_eq!(transition, deserialized);
+        }
+    }
+
+    #[test]
+    fn internally_and_adjacently_tagged_enums() {
+        #[derive(Serialize)]
+        struct Inner {
+            revision: u32,
+        }
+
+        #[derive(Serialize)]
+        #[serde(tag = "type")]
+        enum Internal {
+            Create { id: Identifier },
+            Replace(Inner),
+        }
+
+        #[derive(Serialize)]
+        #[serde(tag = "t", content = "c")]
+        enum Adjacent {
+            Create(Identifier),
+            Move(i64, i64),
+        }
+
+        let internal = Internal::Create {
+            id: Identifier::from([1_u8; 32]),
+        }
+        .serialize(ToDashValue::default())
+        .expect("no errors");
+        assert_eq!(
+            Some(&Value::String(String::from("Create"))),
+            internal.get("type")
+        );
+        assert!(matches!(internal.get("id"), Some(Value::Identifier(_))));
+
+        let internal = Internal::Replace(Inner { revision: 3 })
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        assert_eq!(
+            Some(&Value::String(String::from("Replace"))),
+            internal.get("type")
+        );
+        assert_eq!(Some(&Value::UInteger(3)), internal.get("revision"));
+
+        let adjacent = Adjacent::Create(Identifier::from([1_u8; 32]))
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        assert_eq!(
+            Some(&Value::String(String::from("Create"))),
+            adjacent.get("t")
+        );
+        assert!(matches!(adjacent.get("c"), Some(Value::Identifier(_))));
+
+        let adjacent = Adjacent::Move(1, 2)
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        assert_eq!(
+            Some(&Value::Array(vec![Value::Integer(1), Value::Integer(2)])),
+            adjacent.get("c")
+        );
+    }
+
+    #[test]
+    fn tuple_structs() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Point(i64, i64);
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Wrapper([u8; 3]);
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Pair(Wrapper, Vec<String>);
+
+        let point = Point(-1, 2)
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        assert_eq!(
+            Value::Array(vec![Value::Integer(-1), Value::Integer(2)]),
+            point
+        );
+
+        let wrapper = Wrapper([1, 2, 3])
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        assert_eq!(
+            Value::Array(vec![
+                Value::UInteger(1),
+                Value::UInteger(2),
+                Value::UInteger(3)
+            ]),
+            wrapper
+        );
+
+        let pair = Pair(Wrapper([1, 2, 3]), vec![String::from("alpha")]);
+        let value = pair.serialize(ToDashValue::default()).expect("no errors");
+        let deserialized: Pair = crate::deserializer::from_dash_value(value).expect("no errors");
+        assert_eq!(pair, deserialized);
+    }
+
+    #[test]
+    fn shape_mismatch_in_special_newtypes_is_an_error() {
+        #[derive(Serialize)]
+        struct Version(String);
+
+        #[derive(Serialize)]
+        #[serde(rename = "identifier")]
+        struct NotAnIdentifier(u64);
+
+        let result = Version(String::from("1.0")).serialize(ToDashValue::default());
+        assert!(matches!(
+            result,
+            Err(Error::UnexpectedType { found, .. }) if found == "String"
+        ));
+
+        let result = NotAnIdentifier(1).serialize(ToDashValue::default());
+        assert!(matches!(result, Err(Error::UnexpectedType { .. })));
+    }
+
+    #[test]
+    fn encodings() {
+        use crate::types::{Bytes, Encoding, Encodings};
+
+        #[derive(Serialize)]
+        struct Example {
+            id: Identifier,
+            ids: Vec<Identifier>,
+            data: Bytes,
+            transition: Transition,
+        }
+
+        let id = Identifier::from([1_u8; 32]);
+        let example = Example {
+            id,
+            ids: vec![id],
+            data: Bytes(vec![0xfb, 0xff]),
+            transition: Transition::Create(id),
+        };
+
+        let encodings = Encodings::all(Encoding::Hex).with_bytes(Encoding::Base64Url);
+        let value = example
+            .serialize(ToDashValue::default().with_encodings(encodings))
+            .expect("no errors");
+
+        let hex_id = Value::String("01".repeat(32));
+        assert_eq!(Some(&hex_id), value.get("id"));
+        assert_eq!(Some(&Value::Array(vec![hex_id.clone()])), value.get("ids"));
+        assert_eq!(
+            Some(&Value::String(String::from("-_8="))),
+            value.get("data")
+        );
+        assert_eq!(
+            Some(&hex_id),
+            value.get("transition").and_then(|t| t.get("Create"))
+        );
+    }
+
+    #[test]
+    fn human_readable() {
+        use crate::types::{Bytes, Encoding, Encodings, StaticBytes};
+
+        #[derive(Serialize)]
+        struct Example {
+            id: Identifier,
+            data: Bytes,
+            entropy: StaticBytes,
+            nested: Vec<Transition>,
+        }
+
+        let example = Example {
+            id: Identifier::from([1_u8; 32]),
+            data: Bytes(vec![0xfb, 0xff]),
+            entropy: StaticBytes([2_u8; 32]),
+            nested: vec![Transition::Create(Identifier::from([3_u8; 32]))],
+        };
+
+        let value = example
+            .serialize(ToDashValue::human_readable())
+            .expect("no errors");
+        assert_eq!(
+            serde_json::to_value(&example).expect("no errors"),
+            serde_json::to_value(&value).expect("no errors")
+        );
+        assert_eq!(
+            Some(&Value::String(String::from("+/8="))),
+            value.get("data")
+        );
+
+        let value = example
+            .serialize(ToDashValue::human_readable().with_encodings(Encodings::all(Encoding::Hex)))
+            .expect("no errors");
+        assert_eq!(
+            Some(&Value::String(String::from("fbff"))),
+            value.get("data")
+        );
+        assert_eq!(Some(&Value::String("02".repeat(32))), value.get("entropy"));
+
+        let value = example
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        assert!(matches!(value.get("id"), Some(Value::Identifier(_))));
+        assert!(matches!(value.get("data"), Some(Value::Bytes(_))));
+        assert!(matches!(value.get("entropy"), Some(Value::StaticBytes(_))));
+    }
+
+    #[test]
+    fn human_readable_round_trip() {
+        use crate::deserializer::{from_dash_value, from_dash_value_human_readable, FromDashValue};
+        use crate::types::{Bytes, Encoding, Encodings, StaticBytes};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Example {
+            id: Identifier,
+            data: Bytes,
+            entropy: StaticBytes,
+            version: Version,
+            nested: Vec<Transition>,
+        }
+
+        let example = Example {
+            id: Identifier::from([1_u8; 32]),
+            data: Bytes(vec![0xfb, 0xff]),
+            entropy: StaticBytes([2_u8; 32]),
+            version: Version(1),
+            nested: vec![Transition::Create(Identifier::from([3_u8; 32]))],
+        };
+
+        let value = example
+            .serialize(ToDashValue::human_readable())
+            .expect("no errors");
+        assert_eq!(
+            example,
+            from_dash_value_human_readable(value).expect("no errors")
+        );
+
+        let encodings = Encodings::all(Encoding::Hex);
+        let value = example
+            .serialize(ToDashValue::human_readable().with_encodings(encodings))
+            .expect("no errors");
+        let decoded: Example = FromDashValue::human_readable()
+            .with_encodings(encodings)
+            .deserialize(value)
+            .expect("no errors");
+        assert_eq!(example, decoded);
+
+        let value = example
+            .serialize(ToDashValue::default())
+            .expect("no errors");
+        assert_eq!(example, from_dash_value(value).expect("no errors"));
+    }
+
+    #[test]
+    fn identifier_length_is_validated() {
+        #[derive(Serialize)]
+        #[serde(rename = "identifier")]
+        struct ShortIdentifier(#[serde(with = "serde_bytes_like")] Vec<u8>);
+
+        mod serde_bytes_like {
+            pub fn serialize<S: serde::Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_bytes(v)
+            }
+        }
+
+        let result = ShortIdentifier(vec![1_u8; 5]).serialize(ToDashValue::default());
+        assert!(matches!(
+            result,
+            Err(Error::InvalidLength {
+                expected: 32,
+                found: 5
+            })
+        ));
+    }
 }
@@ -1,27 +1,42 @@
-// type Result<K> = std::result::Result<K, DocumentError>;
 use std::marker::PhantomData;
 
 use serde::Serialize;
 
 use crate::error::Error;
 
+/// Compound serializer for shapes that the parent serializer cannot produce. It carries the
+/// reason it was constructed and returns [`Error::Unsupported`] from the first call made on it
 pub struct Unsupported<O> {
+    reason: &'static str,
     _phantom: PhantomData<O>,
 }
 
+impl<O> Unsupported<O> {
+    pub fn new(reason: &'static str) -> Self {
+        Self {
+            reason,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn error(&self) -> Error {
+        Error::unsupported(self.reason)
+    }
+}
+
 impl<O> serde::ser::SerializeSeq for Unsupported<O> {
     type Error = Error;
     type Ok = O;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(())
+        Err(self.error())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        Err(self.error())
     }
 }
 
@@ -29,15 +44,15 @@ impl<O> serde::ser::SerializeTuple for Unsupported<O> {
     type Error = Error;
     type Ok = O;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(())
+        Err(self.error())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        Err(self.error())
     }
 }
 
@@ -45,15 +60,15 @@ impl<O> serde::ser::SerializeTupleVariant for Unsupported<O> {
     type Error = Error;
     type Ok = O;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(())
+        Err(self.error())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        Err(self.error())
     }
 }
 
@@ -61,19 +76,15 @@ impl<O> serde::ser::SerializeStructVariant for Unsupported<O> {
     type Error = Error;
     type Ok = O;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _key: &'static str, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(())
+        Err(self.error())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        Err(self.error())
     }
 }
 
@@ -81,15 +92,15 @@ impl<O> serde::ser::SerializeTupleStruct for Unsupported<O> {
     type Error = Error;
     type Ok = O;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(())
+        Err(self.error())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        Err(self.error())
     }
 }
 
@@ -97,22 +108,22 @@ impl<O> serde::ser::SerializeMap for Unsupported<O> {
     type Error = Error;
     type Ok = O;
 
-    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
+    fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(())
+        Err(self.error())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(())
+        Err(self.error())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        Err(self.error())
     }
 }
 
@@ -120,18 +131,34 @@ impl<O> serde::ser::SerializeStruct for Unsupported<O> {
     type Error = Error;
     type Ok = O;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        _key: &'static str,
-        _value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _key: &'static str, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(())
+        Err(self.error())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        unimplemented!()
+        Err(self.error())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::ser::{SerializeMap, SerializeSeq};
+
+    use super::Unsupported;
+    use crate::error::Error;
+
+    #[test]
+    fn first_call_returns_reason() {
+        let mut seq: Unsupported<String> = Unsupported::new("sequence as a map key");
+        let result = seq.serialize_element(&1_u8);
+        assert!(
+            matches!(result, Err(Error::Unsupported(reason)) if reason == "sequence as a map key")
+        );
+
+        let map: Unsupported<String> = Unsupported::new("map as a map key");
+        assert!(matches!(SerializeMap::end(map), Err(Error::Unsupported(_))));
     }
 }
@@ -2,12 +2,13 @@ use serde::Serialize;
 
 use super::to_value::ToDashValue;
 use crate::error::Error;
-use crate::types::DocumentValue as Value;
+use crate::types::{DocumentValue as Value, ValueMap};
 
 type Result<K> = std::result::Result<K, Error>;
 
 pub struct SerializeVec {
     pub vec: Vec<Value>,
+    pub serializer: ToDashValue,
 }
 
 impl serde::ser::SerializeSeq for SerializeVec {
@@ -19,7 +20,7 @@ impl serde::ser::SerializeSeq for SerializeVec {
     where
         T: ?Sized + Serialize,
     {
-        self.vec.push(value.serialize(ToDashValue::default())?);
+        self.vec.push(value.serialize(self.serializer)?);
         Ok(())
     }
 
@@ -43,3 +44,44 @@ impl serde::ser::SerializeTuple for SerializeVec {
         serde::ser::SerializeSeq::end(self)
     }
 }
+
+impl serde::ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+pub struct SerializeTupleVariant {
+    pub name: String,
+    pub vec: Vec<Value>,
+    pub serializer: ToDashValue,
+}
+
+impl serde::ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.vec.push(value.serialize(self.serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        let mut map = ValueMap::with_capacity(1);
+        map.insert(self.name, Value::Array(self.vec));
+        Ok(Value::Map(map))
+    }
+}
@@ -0,0 +1,127 @@
+use serde::{ser::Error as _, Serialize};
+
+use super::to_value::ToDashValue;
+use crate::types::Encodings;
+
+/// Wrapper that serializes identifiers and bytes of the inner value as strings with given
+/// encodings, in any format. I.e. `serde_json::to_string(&WithEncodings::new(&doc, encodings))`.
+/// The output is read back with [`deserialize_with_encodings`](crate::deserializer::deserialize_with_encodings)
+pub struct WithEncodings<'a, T: ?Sized> {
+    value: &'a T,
+    encodings: Encodings,
+}
+
+impl<'a, T: ?Sized> WithEncodings<'a, T> {
+    pub fn new(value: &'a T, encodings: Encodings) -> Self {
+        Self { value, encodings }
+    }
+}
+
+impl<'a, T> Serialize for WithEncodings<'a, T>
+where
+    T: ?Sized + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let value = self
+            .value
+            .serialize(ToDashValue::default().with_encodings(self.encodings))
+            .map_err(S::Error::custom)?;
+        value.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::deserializer::{deserialize_with_encodings, FromDashValue};
+    use crate::types::{Bytes, Encoding, Identifier, StaticBytes};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Example {
+        id: Identifier,
+        data: Bytes,
+    }
+
+    #[test]
+    fn serialize_to_json() {
+        let example = Example {
+            id: Identifier::from([1_u8; 32]),
+            data: Bytes(vec![0xfb, 0xff]),
+        };
+
+        let json =
+            serde_json::to_value(WithEncodings::new(&example, Encodings::all(Encoding::Hex)))
+                .expect("no error");
+        assert_eq!(
+            serde_json::json!({ "id": "01".repeat(32), "data": "fbff" }),
+            json
+        );
+
+        let json = serde_json::to_value(WithEncodings::new(&example, Encodings::default()))
+            .expect("no error");
+        assert_eq!(serde_json::to_value(&example).expect("no error"), json);
+    }
+
+    #[test]
+    fn round_trip_through_json() {
+        let example = Example {
+            id: Identifier::from([1_u8; 32]),
+            data: Bytes(vec![0xfb, 0xff]),
+        };
+
+        for encodings in [
+            Encodings::all(Encoding::Hex),
+            Encodings::all(Encoding::Base64),
+            Encodings::all(Encoding::Base64UrlUnpadded).with_identifier(Encoding::Hex),
+        ] {
+            let json =
+                serde_json::to_string(&WithEncodings::new(&example, encodings)).expect("no error");
+            let decoded: Example = deserialize_with_encodings(
+                &mut serde_json::Deserializer::from_str(&json),
+                encodings,
+            )
+            .expect("no error");
+            assert_eq!(example, decoded, "{encodings:?}");
+        }
+
+        let json =
+            serde_json::to_string(&WithEncodings::new(&example, Encodings::all(Encoding::Hex)))
+                .expect("no error");
+        let result: Result<Example, _> = deserialize_with_encodings(
+            &mut serde_json::Deserializer::from_str(&json),
+            Encodings::all(Encoding::Base58),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn round_trip_through_value() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct WithStatic {
+            id: Identifier,
+            entropy: StaticBytes,
+            items: Vec<Bytes>,
+        }
+
+        let example = WithStatic {
+            id: Identifier::from([1_u8; 32]),
+            entropy: StaticBytes([2_u8; 32]),
+            items: vec![Bytes(vec![3]), Bytes(vec![])],
+        };
+
+        let encodings = Encodings::all(Encoding::Hex).with_bytes(Encoding::Base64);
+        let value = example
+            .serialize(ToDashValue::default().with_encodings(encodings))
+            .expect("no error");
+        let decoded: WithStatic = FromDashValue::default()
+            .with_encodings(encodings)
+            .deserialize(value)
+            .expect("no error");
+        assert_eq!(example, decoded);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::error::Error;
+use crate::types::{Bytes, DocumentValue, Identifier, Path, PathSegment, StaticBytes};
+
+/// Length of [`DocumentValue::StaticBytes`]
+pub(crate) const STATIC_BYTES_LENGTH: usize = 32;
+
+/// Binary variant of [`DocumentValue`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryKind {
+    Identifier,
+    Bytes,
+    StaticBytes,
+}
+
+impl BinaryKind {
+    /// Converts the value into the binary variant. Integer arrays and other binary variants
+    /// are accepted, strings are decoded from base58 for identifiers and from base64 otherwise
+    /// if `accept_strings` is set. The path is used in errors
+    pub(crate) fn convert(
+        self,
+        value: DocumentValue,
+        accept_strings: bool,
+        path: &Path,
+    ) -> Result<DocumentValue, Error> {
+        let bytes = match value {
+            DocumentValue::Array(array) => array_to_bytes(array, path)?,
+            DocumentValue::String(s) if accept_strings => self.decode(&s, path)?,
+            DocumentValue::Identifier(id) => id.to_vec(),
+            DocumentValue::Bytes(b) => b.0,
+            DocumentValue::StaticBytes(b) => b.0.to_vec(),
+            DocumentValue::Null => return Ok(DocumentValue::Null),
+            other => {
+                return Err(path.error(format!(
+                    "expected binary data, got {}",
+                    other.variant_name()
+                )))
+            }
+        };
+
+        match self {
+            BinaryKind::Bytes => Ok(DocumentValue::Bytes(Bytes(bytes))),
+            BinaryKind::Identifier => {
+                let len = bytes.len();
+                let id = Identifier::try_from(bytes).map_err(|_| {
+                    path.error(format!(
+                        "identifier must be {} bytes long, got {len}",
+                        Identifier::LENGTH
+                    ))
+                })?;
+                Ok(DocumentValue::Identifier(id))
+            }
+            BinaryKind::StaticBytes => {
+                let len = bytes.len();
+                let data: [u8; STATIC_BYTES_LENGTH] = bytes.try_into().map_err(|_| {
+                    path.error(format!("expected {STATIC_BYTES_LENGTH} bytes, got {len}"))
+                })?;
+                Ok(DocumentValue::StaticBytes(StaticBytes(data)))
+            }
+        }
+    }
+
+    fn decode(self, s: &str, path: &Path) -> Result<Vec<u8>, Error> {
+        match self {
+            BinaryKind::Identifier => bs58::decode(s)
+                .into_vec()
+                .map_err(|e| path.error(format!("invalid base58 string: {e}"))),
+            BinaryKind::Bytes | BinaryKind::StaticBytes => {
+                base64::decode(s).map_err(|e| path.error(format!("invalid base64 string: {e}")))
+            }
+        }
+    }
+}
+
+fn array_to_bytes(array: Vec<DocumentValue>, path: &Path) -> Result<Vec<u8>, Error> {
+    array
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let byte = match item {
+                DocumentValue::UInteger(u) => u8::try_from(u).ok(),
+                DocumentValue::Integer(i) => u8::try_from(i).ok(),
+                _ => None,
+            };
+            byte.ok_or_else(|| {
+                path.join(PathSegment::Index(i))
+                    .error("expected an integer in range 0..=255")
+            })
+        })
+        .collect()
+}
@@ -1,26 +1,120 @@
 use std::{
-    fmt::{write, Debug, Display},
+    fmt::{Debug, Display},
     ops::Deref,
 };
 
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Serialize,
+};
+
+use crate::{
+    error::Error,
+    types::{Encoding, Encodings},
+};
 
-#[derive(Default, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// Bytes of arbitrary length, represented as base64 string in human-readable formats
+#[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Bytes(pub Vec<u8>);
 
 impl Serialize for Bytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct("Bytes", &BytesInternal(&self.0))
+    }
+}
+
+// keeps the representation of `Bytes` and `StaticBytes` behind the newtype names, so the
+// value serializer can recognize them
+struct BytesInternal<'a>(&'a [u8]);
+
+impl<'a> Serialize for BytesInternal<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
         if serializer.is_human_readable() {
-            serializer.serialize_str(&base64::encode(&self.0))
+            serializer.serialize_str(&Encodings::default().bytes.encode(self.0))
         } else {
-            serializer.serialize_bytes(&self.0)
+            serializer.serialize_bytes(self.0)
         }
     }
 }
 
+impl Bytes {
+    pub fn encode(&self, encoding: Encoding) -> String {
+        encoding.encode(&self.0)
+    }
+
+    pub fn from_encoded(s: &str, encoding: Encoding) -> Result<Self, Error> {
+        encoding.decode(s).map(Bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Bytes;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "bytes or base64 string")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(self)
+                } else {
+                    deserializer.deserialize_byte_buf(self)
+                }
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Bytes::from_encoded(v, Encodings::default().bytes).map_err(de::Error::custom)
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Bytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(Bytes(v))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut data = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(byte) = seq.next_element()? {
+                    data.push(byte);
+                }
+                Ok(Bytes(data))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct("Bytes", BytesVisitor)
+    }
+}
+
 impl Deref for Bytes {
     type Target = Vec<u8>;
     fn deref(&self) -> &Self::Target {
@@ -30,7 +124,7 @@ impl Deref for Bytes {
 
 impl Display for Bytes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", base64::encode(&self.0))
+        write!(f, "{}", self.encode(Encodings::default().bytes))
     }
 }
 
@@ -48,11 +142,13 @@ impl From<Vec<u8>> for Bytes {
 
 impl Debug for Bytes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "bytes_base64({})", base64::encode(&self.0))
+        let encoding = Encodings::default().bytes;
+        write!(f, "bytes_{encoding}({})", self.encode(encoding))
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// Bytes of fixed length, represented as base64 string in human-readable formats
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StaticBytes<const N: usize = 32>(pub [u8; N]);
 
 impl<const N: usize> Serialize for StaticBytes<N> {
@@ -60,11 +156,92 @@ impl<const N: usize> Serialize for StaticBytes<N> {
     where
         S: serde::Serializer,
     {
-        if serializer.is_human_readable() {
-            serializer.serialize_str(&base64::encode(self.0))
-        } else {
-            serializer.serialize_bytes(&self.0)
+        serializer.serialize_newtype_struct("StaticBytes", &BytesInternal(&self.0))
+    }
+}
+
+impl<const N: usize> StaticBytes<N> {
+    pub fn encode(&self, encoding: Encoding) -> String {
+        encoding.encode(self.0)
+    }
+
+    pub fn from_encoded(s: &str, encoding: Encoding) -> Result<Self, Error> {
+        let bytes = encoding.decode(s)?;
+        let data: [u8; N] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| Error::InvalidLength {
+                expected: N,
+                found: bytes.len(),
+            })?;
+        Ok(StaticBytes(data))
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for StaticBytes<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct StaticBytesVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for StaticBytesVisitor<N> {
+            type Value = StaticBytes<N>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "{} bytes", N)
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(self)
+                } else {
+                    deserializer.deserialize_bytes(self)
+                }
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                let bytes = Encodings::default()
+                    .bytes
+                    .decode(v)
+                    .map_err(de::Error::custom)?;
+                self.visit_bytes(&bytes)
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                let data: [u8; N] = v
+                    .try_into()
+                    .map_err(|_| de::Error::invalid_length(v.len(), &self))?;
+                Ok(StaticBytes(data))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut data = [0_u8; N];
+                for (i, byte) in data.iter_mut().enumerate() {
+                    *byte = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<u8>()?.is_some() {
+                    return Err(de::Error::invalid_length(N + 1, &self));
+                }
+                Ok(StaticBytes(data))
+            }
         }
+
+        deserializer.deserialize_newtype_struct("StaticBytes", StaticBytesVisitor::<N>)
     }
 }
 
@@ -77,7 +254,7 @@ impl<const N: usize> Deref for StaticBytes<N> {
 
 impl<const N: usize> Display for StaticBytes<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", base64::encode(self.0))
+        write!(f, "{}", self.encode(Encodings::default().bytes))
     }
 }
 
@@ -89,7 +266,8 @@ impl<const S: usize> From<[u8; S]> for StaticBytes<S> {
 
 impl<const S: usize> Debug for StaticBytes<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "bytes_base64({})", base64::encode(self.0))
+        let encoding = Encodings::default().bytes;
+        write!(f, "bytes_{encoding}({})", self.encode(encoding))
     }
 }
 
@@ -98,3 +276,88 @@ impl<const N: usize> Default for StaticBytes<N> {
         StaticBytes([0_u8; N])
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{deserializer::from_dash_value, serializer::ToDashValue, types::DocumentValue};
+
+    #[test]
+    fn json_round_trip() {
+        let bytes = Bytes(vec![1, 2, 3, 250]);
+        let json = serde_json::to_string(&bytes).expect("no error");
+        assert_eq!("\"AQID+g==\"", json);
+        assert_eq!(
+            bytes,
+            serde_json::from_str::<Bytes>(&json).expect("no error")
+        );
+
+        let static_bytes = StaticBytes([7_u8; 32]);
+        let json = serde_json::to_string(&static_bytes).expect("no error");
+        assert_eq!(
+            static_bytes,
+            serde_json::from_str::<StaticBytes>(&json).expect("no error")
+        );
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let bytes = Bytes(vec![1, 2, 3, 250]);
+        let encoded = serde_cbor::to_vec(&bytes).expect("no error");
+        assert_eq!(
+            bytes,
+            serde_cbor::from_slice::<Bytes>(&encoded).expect("no error")
+        );
+
+        let static_bytes = StaticBytes([7_u8; 4]);
+        let encoded = serde_cbor::to_vec(&static_bytes).expect("no error");
+        assert_eq!(
+            static_bytes,
+            serde_cbor::from_slice::<StaticBytes<4>>(&encoded).expect("no error")
+        );
+
+        let value = bytes.serialize(ToDashValue::default()).expect("no error");
+        assert_eq!(DocumentValue::Bytes(bytes.clone()), value);
+        assert_eq!(bytes, from_dash_value::<Bytes>(value).expect("no error"));
+    }
+
+    #[test]
+    fn static_bytes_length_is_validated() {
+        let json = serde_json::to_string(&Bytes(vec![1_u8; 5])).expect("no error");
+        let err = serde_json::from_str::<StaticBytes>(&json).unwrap_err();
+        assert!(err.to_string().contains("invalid length 5"));
+
+        let encoded = serde_cbor::to_vec(&Bytes(vec![1_u8; 5])).expect("no error");
+        assert!(serde_cbor::from_slice::<StaticBytes<4>>(&encoded).is_err());
+    }
+
+    #[test]
+    fn encodings() {
+        let bytes = Bytes(vec![0xfb, 0xff]);
+        assert_eq!("fbff", bytes.encode(Encoding::Hex));
+        assert_eq!(
+            bytes,
+            Bytes::from_encoded("-_8", Encoding::Base64UrlUnpadded).expect("no error")
+        );
+
+        let static_bytes = StaticBytes([0xfb_u8, 0xff]);
+        assert_eq!("+/8=", static_bytes.encode(Encoding::Base64));
+        assert_eq!(
+            static_bytes,
+            StaticBytes::from_encoded("fbff", Encoding::Hex).expect("no error")
+        );
+        assert!(matches!(
+            StaticBytes::<2>::from_encoded("fbffff", Encoding::Hex),
+            Err(Error::InvalidLength {
+                expected: 2,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_base64() {
+        assert!(serde_json::from_str::<Bytes>("\"not base64!\"").is_err());
+        assert!(serde_json::from_str::<StaticBytes>("\"not base64!\"").is_err());
+    }
+}
@@ -0,0 +1,443 @@
+use crate::{
+    error::Error,
+    types::{ordering::Number, Bytes, DocumentValue, Identifier, StaticBytes, ValueMap, Version},
+};
+
+// integers up to 2^53 are exactly representable as f64
+const MAX_SAFE_FLOAT_INTEGER: u128 = 1 << 53;
+
+macro_rules! impl_from {
+    ($($ty:ty => $variant:ident as $target:ty),* $(,)?) => {
+        $(
+            impl From<$ty> for DocumentValue {
+                fn from(v: $ty) -> Self {
+                    DocumentValue::$variant(v as $target)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(
+    i8 => Integer as i64,
+    i16 => Integer as i64,
+    i32 => Integer as i64,
+    i64 => Integer as i64,
+    isize => Integer as i64,
+    u8 => UInteger as u64,
+    u16 => UInteger as u64,
+    u32 => UInteger as u64,
+    u64 => UInteger as u64,
+    usize => UInteger as u64,
+    f32 => Float as f64,
+    f64 => Float as f64,
+);
+
+impl From<i128> for DocumentValue {
+    fn from(v: i128) -> Self {
+        DocumentValue::from_i128(v)
+    }
+}
+
+impl From<u128> for DocumentValue {
+    fn from(v: u128) -> Self {
+        DocumentValue::from_u128(v)
+    }
+}
+
+impl From<bool> for DocumentValue {
+    fn from(v: bool) -> Self {
+        DocumentValue::Bool(v)
+    }
+}
+
+impl From<String> for DocumentValue {
+    fn from(v: String) -> Self {
+        DocumentValue::String(v)
+    }
+}
+
+impl From<&str> for DocumentValue {
+    fn from(v: &str) -> Self {
+        DocumentValue::String(String::from(v))
+    }
+}
+
+impl From<Identifier> for DocumentValue {
+    fn from(v: Identifier) -> Self {
+        DocumentValue::Identifier(v)
+    }
+}
+
+impl From<Bytes> for DocumentValue {
+    fn from(v: Bytes) -> Self {
+        DocumentValue::Bytes(v)
+    }
+}
+
+impl From<StaticBytes<32>> for DocumentValue {
+    fn from(v: StaticBytes<32>) -> Self {
+        DocumentValue::StaticBytes(v)
+    }
+}
+
+impl From<Version> for DocumentValue {
+    fn from(v: Version) -> Self {
+        DocumentValue::Version(v.0)
+    }
+}
+
+impl From<ValueMap> for DocumentValue {
+    fn from(v: ValueMap) -> Self {
+        DocumentValue::Map(v)
+    }
+}
+
+impl<T: Into<DocumentValue>> From<Vec<T>> for DocumentValue {
+    fn from(v: Vec<T>) -> Self {
+        DocumentValue::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+
+/// `None` becomes [`DocumentValue::Null`]
+impl<T: Into<DocumentValue>> From<Option<T>> for DocumentValue {
+    fn from(v: Option<T>) -> Self {
+        v.map(Into::into).unwrap_or_default()
+    }
+}
+
+impl DocumentValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, DocumentValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Result<bool, Error> {
+        match self {
+            DocumentValue::Bool(b) => Ok(*b),
+            other => Err(other.unexpected("Bool")),
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&str, Error> {
+        match self {
+            DocumentValue::String(s) => Ok(s),
+            other => Err(other.unexpected("String")),
+        }
+    }
+
+    /// Any integer variant or `Version`, if the value fits into `i64`
+    pub fn as_i64(&self) -> Result<i64, Error> {
+        self.as_integer("i64")
+    }
+
+    /// Any integer variant or `Version`, if the value fits into `u64`
+    pub fn as_u64(&self) -> Result<u64, Error> {
+        self.as_integer("u64")
+    }
+
+    /// Any integer variant or `Version`, if the value fits into `i128`
+    pub fn as_i128(&self) -> Result<i128, Error> {
+        self.as_integer("i128")
+    }
+
+    /// Any integer variant or `Version`
+    pub fn as_u128(&self) -> Result<u128, Error> {
+        self.as_integer("u128")
+    }
+
+    /// `Float` or an integer that is exactly representable as `f64`
+    pub fn as_f64(&self) -> Result<f64, Error> {
+        match self.number() {
+            Some(Number::Float(f)) => Ok(f),
+            Some(Number::Int(i)) if i.unsigned_abs() <= MAX_SAFE_FLOAT_INTEGER => Ok(i as f64),
+            Some(Number::Int(i)) => Err(Error::out_of_range(i, "f64")),
+            Some(Number::UInt(u)) => Err(Error::out_of_range(u, "f64")),
+            None => Err(self.unexpected("number")),
+        }
+    }
+
+    pub fn as_version(&self) -> Result<Version, Error> {
+        match self {
+            DocumentValue::Version(v) => Ok(Version(*v)),
+            other => Err(other.unexpected("Version")),
+        }
+    }
+
+    pub fn as_identifier(&self) -> Result<&Identifier, Error> {
+        match self {
+            DocumentValue::Identifier(id) => Ok(id),
+            other => Err(other.unexpected("Identifier")),
+        }
+    }
+
+    /// Data of any binary variant: `Bytes`, `StaticBytes` or `Identifier`
+    pub fn as_bytes(&self) -> Result<&[u8], Error> {
+        self.binary_data()
+            .ok_or_else(|| self.unexpected("binary data"))
+    }
+
+    pub fn as_array(&self) -> Result<&Vec<DocumentValue>, Error> {
+        match self {
+            DocumentValue::Array(array) => Ok(array),
+            other => Err(other.unexpected("Array")),
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Result<&mut Vec<DocumentValue>, Error> {
+        match self {
+            DocumentValue::Array(array) => Ok(array),
+            other => Err(other.unexpected("Array")),
+        }
+    }
+
+    pub fn as_map(&self) -> Result<&ValueMap, Error> {
+        match self {
+            DocumentValue::Map(map) => Ok(map),
+            other => Err(other.unexpected("Map")),
+        }
+    }
+
+    pub fn as_map_mut(&mut self) -> Result<&mut ValueMap, Error> {
+        match self {
+            DocumentValue::Map(map) => Ok(map),
+            other => Err(other.unexpected("Map")),
+        }
+    }
+
+    pub fn into_string(self) -> Result<String, Error> {
+        match self {
+            DocumentValue::String(s) => Ok(s),
+            other => Err(other.unexpected("String")),
+        }
+    }
+
+    pub fn into_identifier(self) -> Result<Identifier, Error> {
+        match self {
+            DocumentValue::Identifier(id) => Ok(id),
+            other => Err(other.unexpected("Identifier")),
+        }
+    }
+
+    /// Data of any binary variant: `Bytes`, `StaticBytes` or `Identifier`
+    pub fn into_bytes(self) -> Result<Vec<u8>, Error> {
+        match self {
+            DocumentValue::Bytes(b) => Ok(b.0),
+            DocumentValue::StaticBytes(b) => Ok(b.0.to_vec()),
+            DocumentValue::Identifier(id) => Ok(id.to_vec()),
+            other => Err(other.unexpected("binary data")),
+        }
+    }
+
+    pub fn into_array(self) -> Result<Vec<DocumentValue>, Error> {
+        match self {
+            DocumentValue::Array(array) => Ok(array),
+            other => Err(other.unexpected("Array")),
+        }
+    }
+
+    pub fn into_map(self) -> Result<ValueMap, Error> {
+        match self {
+            DocumentValue::Map(map) => Ok(map),
+            other => Err(other.unexpected("Map")),
+        }
+    }
+
+    fn as_integer<T>(&self, target: &str) -> Result<T, Error>
+    where
+        T: TryFrom<i128> + TryFrom<u128>,
+    {
+        match self.number() {
+            Some(Number::Int(i)) => T::try_from(i).map_err(|_| Error::out_of_range(i, target)),
+            Some(Number::UInt(u)) => T::try_from(u).map_err(|_| Error::out_of_range(u, target)),
+            _ => Err(self.unexpected("integer")),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        Error::unexpected_type(expected, self.variant_name())
+    }
+}
+
+macro_rules! impl_try_from_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<&DocumentValue> for $ty {
+                type Error = Error;
+
+                fn try_from(value: &DocumentValue) -> Result<Self, Self::Error> {
+                    value.as_integer(stringify!($ty))
+                }
+            }
+
+            impl TryFrom<DocumentValue> for $ty {
+                type Error = Error;
+
+                fn try_from(value: DocumentValue) -> Result<Self, Self::Error> {
+                    value.as_integer(stringify!($ty))
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_try_from {
+    ($($ty:ty => $into:ident),* $(,)?) => {
+        $(
+            impl TryFrom<DocumentValue> for $ty {
+                type Error = Error;
+
+                fn try_from(value: DocumentValue) -> Result<Self, Self::Error> {
+                    value.$into()
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from!(
+    bool => as_bool,
+    f64 => as_f64,
+    Version => as_version,
+    String => into_string,
+    Identifier => into_identifier,
+    Vec<DocumentValue> => into_array,
+    ValueMap => into_map,
+);
+
+impl TryFrom<DocumentValue> for Bytes {
+    type Error = Error;
+
+    fn try_from(value: DocumentValue) -> Result<Self, Self::Error> {
+        value.into_bytes().map(Bytes)
+    }
+}
+
+impl TryFrom<DocumentValue> for StaticBytes<32> {
+    type Error = Error;
+
+    fn try_from(value: DocumentValue) -> Result<Self, Self::Error> {
+        let bytes = value.into_bytes()?;
+        let data: [u8; 32] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| Error::InvalidLength {
+                expected: 32,
+                found: bytes.len(),
+            })?;
+        Ok(StaticBytes(data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn accessors() {
+        assert!(DocumentValue::Bool(true).as_bool().expect("no error"));
+        assert_eq!("a", DocumentValue::from("a").as_str().expect("no error"));
+        assert_eq!(5, DocumentValue::Version(5).as_u64().expect("no error"));
+        assert_eq!(2.0, DocumentValue::UInteger(2).as_f64().expect("no error"));
+        assert_eq!(
+            &[1_u8; 32][..],
+            DocumentValue::Identifier(Identifier::from([1_u8; 32]))
+                .as_bytes()
+                .expect("no error")
+        );
+        assert!(DocumentValue::Null.is_null());
+
+        let mut value = DocumentValue::Array(vec![DocumentValue::Null]);
+        value.as_array_mut().expect("no error").push(1.into());
+        assert_eq!(2, value.as_array().expect("no error").len());
+        assert_eq!(2, value.into_array().expect("no error").len());
+    }
+
+    #[test]
+    fn unexpected_type() {
+        let err = DocumentValue::UInteger(1).as_str().unwrap_err();
+        assert!(matches!(
+            err,
+            Error::UnexpectedType { ref expected, ref found } if expected == "String" && found == "UInteger"
+        ));
+
+        assert!(matches!(
+            DocumentValue::Float(1.0).as_i64(),
+            Err(Error::UnexpectedType { .. })
+        ));
+        assert!(matches!(
+            DocumentValue::Null.into_map(),
+            Err(Error::UnexpectedType { .. })
+        ));
+    }
+
+    #[test]
+    fn checked_numeric_conversions() {
+        assert!(matches!(
+            DocumentValue::UInteger(u64::MAX).as_i64(),
+            Err(Error::OutOfRange { ref target, .. }) if target == "i64"
+        ));
+        assert!(matches!(
+            DocumentValue::Integer(-1).as_u64(),
+            Err(Error::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            u8::try_from(DocumentValue::UInteger(256)),
+            Err(Error::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            DocumentValue::UInteger(u64::MAX).as_f64(),
+            Err(Error::OutOfRange { .. })
+        ));
+
+        assert_eq!(
+            255_u8,
+            u8::try_from(DocumentValue::UInteger(255)).expect("no error")
+        );
+        assert_eq!(
+            i64::MAX,
+            i64::try_from(&DocumentValue::UInteger(i64::MAX as u64)).expect("no error")
+        );
+        assert_eq!(
+            u128::MAX,
+            DocumentValue::UInteger128(u128::MAX)
+                .as_u128()
+                .expect("no error")
+        );
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        let id = Identifier::from([1_u8; 32]);
+        assert_eq!(
+            id,
+            Identifier::try_from(DocumentValue::from(id)).expect("no error")
+        );
+
+        let s = String::from("text");
+        assert_eq!(
+            s,
+            String::try_from(DocumentValue::from(s.clone())).expect("no error")
+        );
+
+        let bytes = StaticBytes([2_u8; 32]);
+        assert_eq!(
+            bytes,
+            StaticBytes::try_from(DocumentValue::from(bytes.clone())).expect("no error")
+        );
+        assert!(matches!(
+            StaticBytes::try_from(DocumentValue::Bytes(Bytes(vec![1]))),
+            Err(Error::InvalidLength { .. })
+        ));
+
+        assert_eq!(
+            -3_i32,
+            i32::try_from(DocumentValue::from(-3_i32)).expect("no error")
+        );
+        assert_eq!(
+            Version(4),
+            Version::try_from(DocumentValue::from(Version(4))).expect("no error")
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use crate::types::{DocumentValue, PatchOperation, Path, PathSegment};
+
+/// Difference between two [`DocumentValue`]s at the path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    Added {
+        path: Path,
+        value: DocumentValue,
+    },
+    Removed {
+        path: Path,
+        value: DocumentValue,
+    },
+    Changed {
+        path: Path,
+        old: DocumentValue,
+        new: DocumentValue,
+    },
+}
+
+impl Change {
+    pub fn path(&self) -> &Path {
+        match self {
+            Change::Added { path, .. }
+            | Change::Removed { path, .. }
+            | Change::Changed { path, .. } => path,
+        }
+    }
+
+    pub fn to_patch_operation(&self) -> PatchOperation {
+        match self {
+            Change::Added { path, value } => PatchOperation::Add {
+                path: path.to_pointer(),
+                value: value.clone(),
+            },
+            Change::Removed { path, .. } => PatchOperation::Remove {
+                path: path.to_pointer(),
+            },
+            Change::Changed { path, new, .. } => PatchOperation::Replace {
+                path: path.to_pointer(),
+                value: new.clone(),
+            },
+        }
+    }
+}
+
+impl DocumentValue {
+    /// Returns the changes that turn `self` into `other`. Maps are compared by keys, arrays by
+    /// positions: the common elements are compared and the tail is either added or removed.
+    /// Values of different variants are changed, i.e. `Identifier` and `Bytes` with the same
+    /// data, or `Integer(5)` and `UInteger(5)`.
+    ///
+    /// The changes are ordered so they can be applied one by one as a JSON Patch (array
+    /// elements are removed from the end)
+    pub fn diff(&self, other: &DocumentValue) -> Vec<Change> {
+        let mut changes = Vec::new();
+        diff_at(&mut Path::root(), self, other, &mut changes);
+        changes
+    }
+
+    /// The same as [`DocumentValue::diff`], in the form of JSON Patch operations
+    pub fn diff_patch(&self, other: &DocumentValue) -> Vec<PatchOperation> {
+        self.diff(other)
+            .iter()
+            .map(Change::to_patch_operation)
+            .collect()
+    }
+}
+
+fn diff_at(path: &mut Path, old: &DocumentValue, new: &DocumentValue, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (DocumentValue::Map(old), DocumentValue::Map(new)) => {
+            for (key, old_value) in old {
+                path.push(PathSegment::Key(key.clone()));
+                match new.get(key) {
+                    Some(new_value) => diff_at(path, old_value, new_value, changes),
+                    None => changes.push(Change::Removed {
+                        path: path.clone(),
+                        value: old_value.clone(),
+                    }),
+                }
+                path.pop();
+            }
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    changes.push(Change::Added {
+                        path: path.join(PathSegment::Key(key.clone())),
+                        value: new_value.clone(),
+                    });
+                }
+            }
+        }
+        (DocumentValue::Array(old), DocumentValue::Array(new)) => {
+            for (i, (old_value, new_value)) in old.iter().zip(new).enumerate() {
+                path.push(PathSegment::Index(i));
+                diff_at(path, old_value, new_value, changes);
+                path.pop();
+            }
+            for (i, old_value) in old.iter().enumerate().skip(new.len()).rev() {
+                changes.push(Change::Removed {
+                    path: path.join(PathSegment::Index(i)),
+                    value: old_value.clone(),
+                });
+            }
+            for (i, new_value) in new.iter().enumerate().skip(old.len()) {
+                changes.push(Change::Added {
+                    path: path.join(PathSegment::Index(i)),
+                    value: new_value.clone(),
+                });
+            }
+        }
+        _ => {
+            if old != new {
+                changes.push(Change::Changed {
+                    path: path.clone(),
+                    old: old.clone(),
+                    new: new.clone(),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::value;
+
+    #[test]
+    fn diff_maps_and_arrays() {
+        let old = value!({
+            "name": "Cutie",
+            "removed": true,
+            "tags": ["a", "b", "c"],
+            "nested": { "id": id([1_u8; 32]) },
+        });
+        let new = value!({
+            "name": "Lucky",
+            "added": bytes(vec![1]),
+            "tags": ["a", "x"],
+            "nested": { "id": id([2_u8; 32]) },
+        });
+
+        let changes = old.diff(&new);
+        assert_eq!(
+            vec![
+                Change::Changed {
+                    path: Path::parse("name").expect("no error"),
+                    old: value!("Cutie"),
+                    new: value!("Lucky"),
+                },
+                Change::Changed {
+                    path: Path::parse("tags[1]").expect("no error"),
+                    old: value!("b"),
+                    new: value!("x"),
+                },
+                Change::Removed {
+                    path: Path::parse("tags[2]").expect("no error"),
+                    value: value!("c"),
+                },
+                Change::Changed {
+                    path: Path::parse("nested.id").expect("no error"),
+                    old: value!(id([1_u8; 32])),
+                    new: value!(id([2_u8; 32])),
+                },
+                Change::Removed {
+                    path: Path::parse("removed").expect("no error"),
+                    value: value!(true),
+                },
+                Change::Added {
+                    path: Path::parse("added").expect("no error"),
+                    value: value!(bytes(vec![1])),
+                },
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn binary_variants_are_compared_by_type_and_data() {
+        let old = value!({ "a": id([1_u8; 32]), "b": bytes(vec![1]) });
+        let new = value!({ "a": bytes([1_u8; 32]), "b": bytes(vec![1]) });
+
+        let changes = old.diff(&new);
+        assert_eq!(1, changes.len());
+        assert_eq!("/a", changes[0].path().to_pointer());
+
+        assert!(old.diff(&old.clone()).is_empty());
+    }
+
+    #[test]
+    fn diff_as_json_patch() {
+        let old = value!({
+            "tags": ["a", "b", "c", "d"],
+            "items": [{ "n": 1 }],
+            "replaced": [1],
+        });
+        let new = value!({
+            "tags": ["a"],
+            "items": [{ "n": 2 }, { "n": 3 }, null],
+            "replaced": "scalar",
+            "new": { "deep": version(1) },
+        });
+
+        let patch = old.diff_patch(&new);
+        assert_eq!(
+            Some(&PatchOperation::Remove {
+                path: String::from("/tags/3")
+            }),
+            patch.first()
+        );
+
+        let mut patched = old.clone();
+        patched.apply_patch(&patch).expect("no error");
+        assert_eq!(new, patched);
+
+        let mut patched = new.clone();
+        patched
+            .apply_patch(&new.diff_patch(&old))
+            .expect("no error");
+        assert_eq!(old, patched);
+    }
+
+    #[test]
+    fn diff_at_root() {
+        let changes = value!(1).diff(&value!("1"));
+        assert_eq!(1, changes.len());
+        assert!(changes[0].path().is_root());
+
+        let mut patched = value!(1);
+        patched
+            .apply_patch(&value!(1).diff_patch(&value!("1")))
+            .expect("no error");
+        assert_eq!(value!("1"), patched);
+    }
+}
@@ -0,0 +1,175 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::error::Error;
+
+/// Binary-to-text encoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Hex,
+    Base58,
+    /// Standard base64 alphabet with padding
+    Base64,
+    /// Standard base64 alphabet without padding
+    Base64Unpadded,
+    /// URL-safe base64 alphabet with padding
+    Base64Url,
+    /// URL-safe base64 alphabet without padding
+    Base64UrlUnpadded,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 6] = [
+        Encoding::Hex,
+        Encoding::Base58,
+        Encoding::Base64,
+        Encoding::Base64Unpadded,
+        Encoding::Base64Url,
+        Encoding::Base64UrlUnpadded,
+    ];
+
+    pub fn encode(self, data: impl AsRef<[u8]>) -> String {
+        let data = data.as_ref();
+        match self {
+            Encoding::Hex => hex::encode(data),
+            Encoding::Base58 => bs58::encode(data).into_string(),
+            Encoding::Base64 => base64::encode_config(data, base64::STANDARD),
+            Encoding::Base64Unpadded => base64::encode_config(data, base64::STANDARD_NO_PAD),
+            Encoding::Base64Url => base64::encode_config(data, base64::URL_SAFE),
+            Encoding::Base64UrlUnpadded => base64::encode_config(data, base64::URL_SAFE_NO_PAD),
+        }
+    }
+
+    pub fn decode(self, s: &str) -> Result<Vec<u8>, Error> {
+        let result = match self {
+            Encoding::Hex => hex::decode(s).map_err(|e| e.to_string()),
+            Encoding::Base58 => bs58::decode(s).into_vec().map_err(|e| e.to_string()),
+            Encoding::Base64 => decode_base64(s, base64::STANDARD),
+            Encoding::Base64Unpadded => decode_base64(s, base64::STANDARD_NO_PAD),
+            Encoding::Base64Url => decode_base64(s, base64::URL_SAFE),
+            Encoding::Base64UrlUnpadded => decode_base64(s, base64::URL_SAFE_NO_PAD),
+        };
+
+        result.map_err(|e| Error::InvalidEncoding(format!("invalid {self} string: {e}")))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Hex => "hex",
+            Encoding::Base58 => "base58",
+            Encoding::Base64 => "base64",
+            Encoding::Base64Unpadded => "base64-unpadded",
+            Encoding::Base64Url => "base64url",
+            Encoding::Base64UrlUnpadded => "base64url-unpadded",
+        }
+    }
+}
+
+fn decode_base64(s: &str, config: base64::Config) -> Result<Vec<u8>, String> {
+    base64::decode_config(s, config).map_err(|e| e.to_string())
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = Error;
+
+    /// Parses the name of the encoding, as returned by [`Encoding::name`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Encoding::ALL
+            .into_iter()
+            .find(|encoding| encoding.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::InvalidEncoding(format!("unknown encoding '{s}'")))
+    }
+}
+
+/// Encodings used for the binary data when it is converted into text
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Encodings {
+    pub identifier: Encoding,
+    pub bytes: Encoding,
+}
+
+impl Encodings {
+    /// Uses the same encoding for identifiers and bytes
+    pub fn all(encoding: Encoding) -> Self {
+        Self {
+            identifier: encoding,
+            bytes: encoding,
+        }
+    }
+
+    pub fn with_identifier(mut self, encoding: Encoding) -> Self {
+        self.identifier = encoding;
+        self
+    }
+
+    pub fn with_bytes(mut self, encoding: Encoding) -> Self {
+        self.bytes = encoding;
+        self
+    }
+}
+
+/// Base58 for identifiers and base64 for bytes
+impl Default for Encodings {
+    fn default() -> Self {
+        Self {
+            identifier: Encoding::Base58,
+            bytes: Encoding::Base64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        let data = [0xfb_u8, 0xff, 0x01];
+        assert_eq!("fbff01", Encoding::Hex.encode(data));
+        assert_eq!("2NEpo7TZRRrLZSi2U", Encoding::Base58.encode("Hello World!"));
+        assert_eq!("+/8B", Encoding::Base64.encode(data));
+        assert_eq!("-_8B", Encoding::Base64Url.encode(data));
+        assert_eq!("+w==", Encoding::Base64.encode([0xfb]));
+        assert_eq!("+w", Encoding::Base64Unpadded.encode([0xfb]));
+        assert_eq!("-w==", Encoding::Base64Url.encode([0xfb]));
+        assert_eq!("-w", Encoding::Base64UrlUnpadded.encode([0xfb]));
+    }
+
+    #[test]
+    fn round_trip() {
+        let data: Vec<u8> = (0..=255).collect();
+        for encoding in Encoding::ALL {
+            let encoded = encoding.encode(&data);
+            assert_eq!(
+                data,
+                encoding.decode(&encoded).expect("no error"),
+                "{encoding}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(matches!(
+            Encoding::Hex.decode("xyz"),
+            Err(Error::InvalidEncoding(_))
+        ));
+        assert!(Encoding::Base58.decode("0OIl").is_err());
+        assert!(Encoding::Base64.decode("-_8B").is_err());
+        assert!(Encoding::Base64Url.decode("+/8B").is_err());
+    }
+
+    #[test]
+    fn parse_name() {
+        for encoding in Encoding::ALL {
+            assert_eq!(encoding, encoding.name().parse().expect("no error"));
+        }
+        assert_eq!(Encoding::Hex, "HEX".parse().expect("no error"));
+        assert!("base32".parse::<Encoding>().is_err());
+    }
+}
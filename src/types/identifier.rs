@@ -1,12 +1,23 @@
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
 
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Serialize,
+};
+use sha2::{Digest, Sha256};
 
-use crate::types::DocumentValue;
+use crate::{
+    error::Error,
+    types::{Encoding, Encodings},
+};
 
-#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+/// 32 bytes long identifier, represented as base58 string in human-readable formats
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Identifier {
-    pub data: Vec<u8>,
+    pub data: [u8; Identifier::LENGTH],
 }
 
 impl Serialize for Identifier {
@@ -21,25 +32,76 @@ impl Serialize for Identifier {
 
 impl<'de> Deserialize<'de> for Identifier {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct("identifier", IdentifierVisitor)
+    }
+}
+
+struct IdentifierVisitor;
+
+impl<'de> Visitor<'de> for IdentifierVisitor {
+    type Value = Identifier;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "{} bytes or base58 string", Identifier::LENGTH)
+    }
+
+    // human-readable formats can carry the base58 string or the array of bytes
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         if deserializer.is_human_readable() {
-            let id_string: &str = Deserialize::deserialize(deserializer)?;
-            let id_bytes = bs58::decode(id_string)
-                .into_vec()
-                .map_err(|e| serde::de::Error::custom(e.to_string()))?;
-            Ok(Self::from(id_bytes))
+            deserializer.deserialize_any(self)
         } else {
-            let data: DocumentValue = Deserialize::deserialize(deserializer)?;
-            if let DocumentValue::Bytes(bytes) = data {
-                return Ok(Identifier::from(bytes.0));
-            }
-            Err(serde::de::Error::custom(format!(
-                "expected bytes, got: {:?}",
-                data
-            )))
+            deserializer.deserialize_bytes(self)
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Identifier::from_str(v).map_err(de::Error::custom)
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_str(&v)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Identifier::try_from(v).map_err(|_| de::Error::invalid_length(v.len(), &self))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(&v)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut data = [0_u8; Identifier::LENGTH];
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(Identifier::LENGTH + 1, &self));
         }
+        Ok(Identifier { data })
     }
 }
 
@@ -51,7 +113,7 @@ impl<'a> Serialize for IdInternal<'a> {
         S: serde::Serializer,
     {
         if serializer.is_human_readable() {
-            serializer.serialize_str(&bs58::encode(self.0).into_string())
+            serializer.serialize_str(&Encodings::default().identifier.encode(self.0))
         } else {
             serializer.serialize_bytes(self.0)
         }
@@ -60,22 +122,249 @@ impl<'a> Serialize for IdInternal<'a> {
 
 impl Display for Identifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", bs58::encode(&self.data).into_string())
+        write!(f, "{}", self.encode(Encodings::default().identifier))
     }
 }
 
 impl Debug for Identifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "identifier_base58({})",
-            bs58::encode(&self.data).into_string()
-        )
+        let encoding = Encodings::default().identifier;
+        write!(f, "identifier_{encoding}({})", self.encode(encoding))
+    }
+}
+
+impl Identifier {
+    pub const LENGTH: usize = 32;
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.data.to_vec()
+    }
+
+    pub fn encode(&self, encoding: Encoding) -> String {
+        encoding.encode(self.data)
+    }
+
+    pub fn from_encoded(s: &str, encoding: Encoding) -> Result<Self, Error> {
+        Identifier::try_from(encoding.decode(s)?)
+    }
+
+    /// Derives the id of a document the same way the platform does:
+    /// `sha256(sha256(data_contract_id || owner_id || document_type || entropy))`
+    pub fn generate_document_id(
+        data_contract_id: &Identifier,
+        owner_id: &Identifier,
+        document_type: &str,
+        entropy: &[u8; 32],
+    ) -> Identifier {
+        Identifier::from(double_sha256(&[
+            &data_contract_id.data,
+            &owner_id.data,
+            document_type.as_bytes(),
+            entropy,
+        ]))
+    }
+
+    /// Derives the id of a data contract: `sha256(sha256(owner_id || entropy))`
+    pub fn generate_data_contract_id(owner_id: &Identifier, entropy: &[u8; 32]) -> Identifier {
+        Identifier::from(double_sha256(&[&owner_id.data, entropy]))
     }
 }
 
-impl From<Vec<u8>> for Identifier {
-    fn from(v: Vec<u8>) -> Self {
-        Identifier { data: v }
+fn double_sha256(chunks: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for chunk in chunks {
+        hasher.update(chunk);
+    }
+    Sha256::digest(hasher.finalize()).into()
+}
+
+impl From<[u8; Identifier::LENGTH]> for Identifier {
+    fn from(data: [u8; Identifier::LENGTH]) -> Self {
+        Identifier { data }
+    }
+}
+
+impl TryFrom<&[u8]> for Identifier {
+    type Error = Error;
+
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        let data = v.try_into().map_err(|_| Error::InvalidLength {
+            expected: Identifier::LENGTH,
+            found: v.len(),
+        })?;
+        Ok(Identifier { data })
+    }
+}
+
+impl TryFrom<Vec<u8>> for Identifier {
+    type Error = Error;
+
+    fn try_from(v: Vec<u8>) -> Result<Self, Self::Error> {
+        Identifier::try_from(v.as_slice())
+    }
+}
+
+impl FromStr for Identifier {
+    type Err = Error;
+
+    /// Parses identifier encoded with the default encoding, base58
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Identifier::from_encoded(s, Encodings::default().identifier)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{deserializer::from_dash_value, types::DocumentValue};
+
+    #[test]
+    fn double_sha256_of_empty_input() {
+        assert_eq!(
+            "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456",
+            hex::encode(double_sha256(&[]))
+        );
+    }
+
+    #[test]
+    fn generate_document_id() {
+        let contract_id = Identifier::from([1_u8; 32]);
+        let owner_id = Identifier::from([2_u8; 32]);
+        let entropy = [3_u8; 32];
+
+        let id = Identifier::generate_document_id(&contract_id, &owner_id, "note", &entropy);
+
+        let mut input = vec![1_u8; 32];
+        input.extend_from_slice(&[2_u8; 32]);
+        input.extend_from_slice(b"note");
+        input.extend_from_slice(&entropy);
+        assert_eq!(
+            Sha256::digest(Sha256::digest(&input)).as_slice(),
+            id.as_bytes()
+        );
+
+        assert_eq!(
+            id,
+            Identifier::generate_document_id(&contract_id, &owner_id, "note", &entropy)
+        );
+        assert_ne!(
+            id,
+            Identifier::generate_document_id(&contract_id, &owner_id, "other", &entropy)
+        );
+    }
+
+    #[test]
+    fn generate_data_contract_id() {
+        let owner_id = Identifier::from([2_u8; 32]);
+        let entropy = [3_u8; 32];
+
+        let id = Identifier::generate_data_contract_id(&owner_id, &entropy);
+
+        let mut input = vec![2_u8; 32];
+        input.extend_from_slice(&entropy);
+        assert_eq!(
+            Sha256::digest(Sha256::digest(&input)).as_slice(),
+            id.as_bytes()
+        );
+        assert_ne!(
+            id,
+            Identifier::generate_data_contract_id(&owner_id, &[4_u8; 32])
+        );
+    }
+
+    #[test]
+    fn try_from_validates_length() {
+        assert_eq!(
+            Identifier::from([7_u8; 32]),
+            Identifier::try_from(vec![7_u8; 32]).expect("no error")
+        );
+        assert!(matches!(
+            Identifier::try_from(&[7_u8; 5][..]),
+            Err(Error::InvalidLength {
+                expected: 32,
+                found: 5
+            })
+        ));
+        assert!(matches!(
+            Identifier::try_from(vec![7_u8; 100]),
+            Err(Error::InvalidLength {
+                expected: 32,
+                found: 100
+            })
+        ));
+    }
+
+    #[test]
+    fn deserialize_from_owned_strings_and_arrays() {
+        let id = Identifier::from([7_u8; 32]);
+
+        let json = serde_json::Value::String(id.to_string());
+        assert_eq!(id, serde_json::from_value(json).expect("no error"));
+        let json = format!("\"{id}\"");
+        assert_eq!(
+            id,
+            serde_json::from_reader::<_, Identifier>(json.as_bytes()).expect("no error")
+        );
+
+        let array = serde_json::json!(vec![7_u8; 32]);
+        assert_eq!(id, serde_json::from_value(array).expect("no error"));
+        let array = DocumentValue::from(id).bytes_as_arrays();
+        assert_eq!(id, from_dash_value(array).expect("no error"));
+
+        let cbor = serde_cbor::to_vec(&id).expect("no error");
+        assert_eq!(id, serde_cbor::from_slice(&cbor).expect("no error"));
+
+        assert!(serde_json::from_value::<Identifier>(serde_json::json!(vec![7_u8; 31])).is_err());
+        assert!(serde_json::from_value::<Identifier>(serde_json::json!(vec![7_u8; 33])).is_err());
+        assert!(from_dash_value::<Identifier>(DocumentValue::Bytes(vec![7_u8; 5].into())).is_err());
+    }
+
+    #[test]
+    fn from_str() {
+        let id = Identifier::from([7_u8; 32]);
+        assert_eq!(id, Identifier::from_str(&id.to_string()).expect("no error"));
+
+        assert!(matches!(
+            Identifier::from_str("0OIl"),
+            Err(Error::InvalidEncoding(_))
+        ));
+        assert!(matches!(
+            Identifier::from_str(&bs58::encode([7_u8; 5]).into_string()),
+            Err(Error::InvalidLength { found: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn encodings() {
+        let id = Identifier::from([7_u8; 32]);
+        for encoding in Encoding::ALL {
+            let encoded = id.encode(encoding);
+            assert_eq!(
+                id,
+                Identifier::from_encoded(&encoded, encoding).expect("no error")
+            );
+        }
+        assert_eq!("07".repeat(32), id.encode(Encoding::Hex));
+        assert!(matches!(
+            Identifier::from_encoded("0707", Encoding::Hex),
+            Err(Error::InvalidLength { found: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn deserialize_validates_length() {
+        let json = format!("\"{}\"", bs58::encode([7_u8; 5]).into_string());
+        let err = serde_json::from_str::<Identifier>(&json).unwrap_err();
+        assert!(err.to_string().contains("expected 32 bytes, got 5"));
+
+        let value = DocumentValue::Bytes(crate::types::Bytes(vec![7_u8; 5]));
+        assert!(matches!(
+            crate::deserializer::from_dash_value::<Identifier>(value),
+            Err(Error::DeserializationError(_))
+        ));
     }
 }
@@ -1,9 +1,25 @@
+mod binary;
 mod bytes;
+mod convert;
+mod diff;
+mod encoding;
 mod identifier;
+mod ordering;
+mod patch;
+mod path;
 mod value;
+mod value_map;
 mod version;
+mod walk;
 
+pub use binary::*;
 pub use bytes::*;
+pub use diff::*;
+pub use encoding::*;
 pub use identifier::*;
+pub use patch::*;
+pub use path::*;
 pub use value::*;
+pub use value_map::*;
 pub use version::*;
+pub use walk::*;
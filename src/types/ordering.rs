@@ -0,0 +1,483 @@
+//! Equality, hashing and total ordering of [`DocumentValue`].
+//!
+//! Values are ordered first by their kind:
+//!
+//! `Null < Bool < numbers < String < binary data < Array < Map`
+//!
+//! - numbers (`Integer`, `UInteger`, `Integer128`, `UInteger128`, `Float` and `Version`) are
+//!   compared by their numeric value, exactly, without converting integers into floats. `NaN` is
+//!   greater than any other number and all `NaN`s are equal. `-0.0` equals `0.0`
+//! - binary data (`Identifier`, `Bytes` and `StaticBytes`) is compared bytewise
+//! - strings are compared bytewise (by their UTF-8 representation)
+//! - arrays are compared lexicographically, maps are compared entry by entry (key, then value)
+//!   in the canonical key order
+//!
+//! Numbers and binary data of equal value but different variants, i.e. `Integer(5)` and
+//! `UInteger(5)`, are not equal. They are ordered by the variant, in the order of declaration.
+//! To compare values regardless of the numeric variant use [`DocumentValue::loose_eq`],
+//! [`DocumentValue::compare_numeric`] or [`DocumentValue::normalize`].
+
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use crate::types::{DocumentValue, Location, Walk};
+
+/// Numeric value of the number variants
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Number {
+    Int(i128),
+    UInt(u128),
+    Float(f64),
+}
+
+impl Number {
+    pub(crate) fn cmp(self, other: Number) -> Ordering {
+        match (self, other) {
+            (Number::Int(a), Number::Int(b)) => a.cmp(&b),
+            (Number::UInt(a), Number::UInt(b)) => a.cmp(&b),
+            (Number::Int(a), Number::UInt(b)) => match u128::try_from(a) {
+                Ok(a) => a.cmp(&b),
+                Err(_) => Ordering::Less,
+            },
+            (Number::UInt(_), Number::Int(_)) => other.cmp(self).reverse(),
+            (Number::Float(a), Number::Float(b)) => cmp_floats(a, b),
+            (Number::Int(a), Number::Float(b)) => cmp_int_float(a, b),
+            (Number::UInt(a), Number::Float(b)) => cmp_uint_float(a, b),
+            (Number::Float(_), _) => other.cmp(self).reverse(),
+        }
+    }
+}
+
+impl Number {
+    // canonical representation of the number
+    fn into_value(self) -> DocumentValue {
+        match self {
+            Number::Int(i) if i >= 0 => DocumentValue::from_u128(i as u128),
+            Number::Int(i) => DocumentValue::from_i128(i),
+            Number::UInt(u) => DocumentValue::from_u128(u),
+            Number::Float(f) if f.fract() == 0.0 && (0.0..TWO_POW_128).contains(&f) => {
+                DocumentValue::from_u128(f as u128)
+            }
+            Number::Float(f) if f.fract() == 0.0 && (-TWO_POW_127..0.0).contains(&f) => {
+                DocumentValue::from_i128(f as i128)
+            }
+            Number::Float(f) => DocumentValue::Float(f),
+        }
+    }
+}
+
+fn cmp_floats(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    }
+}
+
+// 2^127 and 2^128 are exactly representable as f64
+const TWO_POW_127: f64 = 170141183460469231731687303715884105728.0;
+const TWO_POW_128: f64 = 340282366920938463463374607431768211456.0;
+
+fn cmp_int_float(a: i128, b: f64) -> Ordering {
+    if b.is_nan() || b >= TWO_POW_127 {
+        return Ordering::Less;
+    }
+    if b < -TWO_POW_127 {
+        return Ordering::Greater;
+    }
+    let truncated = b.trunc();
+    a.cmp(&(truncated as i128))
+        .then_with(|| cmp_floats(0.0, b - truncated))
+}
+
+fn cmp_uint_float(a: u128, b: f64) -> Ordering {
+    if b.is_nan() || b >= TWO_POW_128 {
+        return Ordering::Less;
+    }
+    if b < 0.0 {
+        return Ordering::Greater;
+    }
+    let truncated = b.trunc();
+    a.cmp(&(truncated as u128))
+        .then_with(|| cmp_floats(0.0, b - truncated))
+}
+
+impl DocumentValue {
+    /// Numeric value of the number variants, `None` for the others
+    pub(crate) fn number(&self) -> Option<Number> {
+        match self {
+            Self::Integer(i) => Some(Number::Int(*i as i128)),
+            Self::UInteger(u) => Some(Number::Int(*u as i128)),
+            Self::Integer128(i) => Some(Number::Int(*i)),
+            Self::UInteger128(u) => Some(match i128::try_from(*u) {
+                Ok(i) => Number::Int(i),
+                Err(_) => Number::UInt(*u),
+            }),
+            Self::Float(f) => Some(Number::Float(*f)),
+            Self::Version(v) => Some(Number::Int(*v as i128)),
+            _ => None,
+        }
+    }
+
+    // position of the variant, used as a tie-breaker between values of the same kind
+    fn variant_rank(&self) -> u8 {
+        match self {
+            Self::Null => 0,
+            Self::Bool(_) => 1,
+            Self::Integer(_) => 2,
+            Self::UInteger(_) => 3,
+            Self::Integer128(_) => 4,
+            Self::UInteger128(_) => 5,
+            Self::Float(_) => 6,
+            Self::Version(_) => 7,
+            Self::String(_) => 8,
+            Self::Identifier(_) => 9,
+            Self::Bytes(_) => 10,
+            Self::StaticBytes(_) => 11,
+            Self::Array(_) => 12,
+            Self::Map(_) => 13,
+        }
+    }
+
+    fn kind_rank(&self) -> u8 {
+        match self {
+            Self::Null => 0,
+            Self::Bool(_) => 1,
+            Self::Integer(_)
+            | Self::UInteger(_)
+            | Self::Integer128(_)
+            | Self::UInteger128(_)
+            | Self::Float(_)
+            | Self::Version(_) => 2,
+            Self::String(_) => 3,
+            Self::Identifier(_) | Self::Bytes(_) | Self::StaticBytes(_) => 4,
+            Self::Array(_) => 5,
+            Self::Map(_) => 6,
+        }
+    }
+
+    /// Bytes of the binary variants, `None` for the others
+    pub(crate) fn binary_data(&self) -> Option<&[u8]> {
+        match self {
+            Self::Identifier(id) => Some(id.as_bytes()),
+            Self::Bytes(b) => Some(b.as_slice()),
+            Self::StaticBytes(b) => Some(b.as_slice()),
+            _ => None,
+        }
+    }
+}
+
+impl DocumentValue {
+    /// Compares the numeric values of two numbers, regardless of their variants.
+    /// `NaN` is greater than any other number. Returns `None` if any of the values isn't a number
+    pub fn compare_numeric(&self, other: &DocumentValue) -> Option<Ordering> {
+        Some(self.number()?.cmp(other.number()?))
+    }
+
+    /// Equality that ignores the numeric variants: `Integer(5)`, `UInteger(5)`, `Version(5)`
+    /// and `Float(5.0)` are all equal. Arrays and maps are compared recursively.
+    /// `a.loose_eq(&b)` is the same as `a.normalized() == b.normalized()`
+    pub fn loose_eq(&self, other: &DocumentValue) -> bool {
+        match (self, other) {
+            (Self::Array(a), Self::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.loose_eq(b))
+            }
+            (Self::Map(a), Self::Map(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b.iter())
+                        .all(|((ka, va), (kb, vb))| ka == kb && va.loose_eq(vb))
+            }
+            _ => match self.compare_numeric(other) {
+                Some(ordering) => ordering == Ordering::Equal,
+                None => self == other,
+            },
+        }
+    }
+
+    /// Collapses the numbers into the canonical form, recursively: non-negative integers become
+    /// `UInteger` (or `UInteger128`), negative ones `Integer` (or `Integer128`). `Version` and
+    /// floats without the fractional part are converted into integers as well
+    pub fn normalize(&mut self) {
+        self.walk_mut(&mut |_: &Location, value: &mut DocumentValue| {
+            if let Some(number) = value.number() {
+                *value = number.into_value();
+            }
+            Walk::Continue
+        });
+    }
+
+    pub fn normalized(mut self) -> DocumentValue {
+        self.normalize();
+        self
+    }
+}
+
+impl Ord for DocumentValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_value = match (self, other) {
+            (Self::Bool(a), Self::Bool(b)) => a.cmp(b),
+            (Self::String(a), Self::String(b)) => a.cmp(b),
+            (Self::Array(a), Self::Array(b)) => a.cmp(b),
+            (Self::Map(a), Self::Map(b)) => a.cmp(b),
+            _ => {
+                if let (Some(a), Some(b)) = (self.number(), other.number()) {
+                    a.cmp(b)
+                } else if let (Some(a), Some(b)) = (self.binary_data(), other.binary_data()) {
+                    a.cmp(b)
+                } else {
+                    self.kind_rank().cmp(&other.kind_rank())
+                }
+            }
+        };
+        by_value.then_with(|| self.variant_rank().cmp(&other.variant_rank()))
+    }
+}
+
+impl PartialOrd for DocumentValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for DocumentValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DocumentValue {}
+
+impl Hash for DocumentValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.variant_rank().hash(state);
+        match self {
+            Self::Null => {}
+            Self::Bool(b) => b.hash(state),
+            Self::String(s) => s.hash(state),
+            Self::Array(array) => array.hash(state),
+            Self::Map(map) => map.hash(state),
+            Self::Float(f) => {
+                // all NaNs are equal, so are 0.0 and -0.0
+                let f = if f.is_nan() {
+                    f64::NAN
+                } else if *f == 0.0 {
+                    0.0
+                } else {
+                    *f
+                };
+                f.to_bits().hash(state)
+            }
+            _ => {
+                if let Some(data) = self.binary_data() {
+                    data.hash(state)
+                } else if let Some(Number::Int(i)) = self.number() {
+                    i.hash(state)
+                } else if let Some(Number::UInt(u)) = self.number() {
+                    u.hash(state)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeSet, HashSet};
+
+    use super::*;
+    use crate::types::{Bytes, Identifier, StaticBytes, ValueMap};
+
+    #[test]
+    fn order_of_kinds() {
+        let values = vec![
+            DocumentValue::Map(ValueMap::new()),
+            DocumentValue::Array(vec![]),
+            DocumentValue::Bytes(Bytes(vec![])),
+            DocumentValue::String(String::new()),
+            DocumentValue::Integer(i64::MAX),
+            DocumentValue::Float(f64::NEG_INFINITY),
+            DocumentValue::Bool(true),
+            DocumentValue::Null,
+        ];
+        let mut sorted = values.clone();
+        sorted.sort();
+        assert_eq!(values.into_iter().rev().collect::<Vec<_>>(), sorted);
+    }
+
+    #[test]
+    fn numbers_compare_by_value() {
+        use DocumentValue::*;
+
+        assert!(Integer(-1) < UInteger(0));
+        assert!(UInteger(u64::MAX) < UInteger128(u64::MAX as u128 + 1));
+        assert!(Integer128(i128::MIN) < Integer(i64::MIN));
+        assert!(UInteger128(u128::MAX) > Integer128(i128::MAX));
+        assert!(Float(2.5) > Integer(2));
+        assert!(Float(2.5) < UInteger(3));
+        assert!(Float(-2.5) < Integer(-2));
+        assert!(Float(-2.5) > Integer(-3));
+        assert!(Float(1e40) > UInteger128(u128::MAX));
+        assert!(Float(f64::INFINITY) > UInteger128(u128::MAX));
+        assert!(Float(f64::NEG_INFINITY) < Integer128(i128::MIN));
+        assert!(Version(3) > Float(2.9));
+
+        // equal numbers are ordered by the variant
+        assert!(Integer(5) < UInteger(5));
+        assert!(UInteger(5) < Float(5.0));
+        assert!(Float(5.0) < Version(5));
+        assert_ne!(Integer(5), UInteger(5));
+    }
+
+    #[test]
+    fn nan_is_the_greatest_number() {
+        use DocumentValue::*;
+
+        assert_eq!(Float(f64::NAN), Float(-f64::NAN));
+        assert!(Float(f64::NAN) > Float(f64::INFINITY));
+        assert!(Float(f64::NAN) > UInteger128(u128::MAX));
+        assert!(Float(f64::NAN) < String(std::string::String::new()));
+        assert_eq!(Float(0.0), Float(-0.0));
+    }
+
+    #[test]
+    fn binary_data_compares_bytewise() {
+        let id = DocumentValue::Identifier(Identifier::from([1_u8; 32]));
+        let bytes = DocumentValue::Bytes(Bytes(vec![1_u8; 32]));
+        let static_bytes = DocumentValue::StaticBytes(StaticBytes([1_u8; 32]));
+
+        assert!(id < bytes);
+        assert!(bytes < static_bytes);
+        assert_ne!(id, bytes);
+        assert!(DocumentValue::Bytes(Bytes(vec![2])) > id);
+        assert!(DocumentValue::Bytes(Bytes(vec![1, 1])) < id);
+    }
+
+    #[test]
+    fn containers() {
+        let short = DocumentValue::Array(vec![DocumentValue::UInteger(1)]);
+        let long = DocumentValue::Array(vec![DocumentValue::UInteger(1), DocumentValue::Null]);
+        assert!(short < long);
+
+        let mut a = ValueMap::new();
+        a.insert("a", DocumentValue::UInteger(1));
+        let mut b = ValueMap::new();
+        b.insert("a", DocumentValue::UInteger(2));
+        assert!(DocumentValue::Map(a) < DocumentValue::Map(b));
+
+        // shorter keys go first, unlike in the bytewise order of strings
+        let short_key: ValueMap = [("b", DocumentValue::Null)].into_iter().collect();
+        let long_key: ValueMap = [("aa", DocumentValue::Null)].into_iter().collect();
+        assert!(short_key < long_key);
+        assert!(DocumentValue::Map(short_key.clone()) < DocumentValue::Map(long_key));
+
+        let longer: ValueMap = [("b", DocumentValue::Null), ("aa", DocumentValue::Null)]
+            .into_iter()
+            .collect();
+        assert!(short_key < longer);
+    }
+
+    #[test]
+    fn hash_is_consistent_with_eq() {
+        let values = [
+            DocumentValue::Float(f64::NAN),
+            DocumentValue::Float(-f64::NAN),
+            DocumentValue::Float(0.0),
+            DocumentValue::Float(-0.0),
+            DocumentValue::Integer(5),
+            DocumentValue::UInteger(5),
+            DocumentValue::Bytes(Bytes(vec![1])),
+            DocumentValue::Bytes(Bytes(vec![1])),
+        ];
+
+        let set: HashSet<_> = values.iter().cloned().collect();
+        assert_eq!(5, set.len());
+
+        let set: BTreeSet<_> = values.into_iter().collect();
+        assert_eq!(5, set.len());
+    }
+
+    #[test]
+    fn compare_numeric() {
+        use DocumentValue::*;
+
+        assert_eq!(
+            Some(Ordering::Equal),
+            Integer(5).compare_numeric(&UInteger(5))
+        );
+        assert_eq!(
+            Some(Ordering::Equal),
+            Version(5).compare_numeric(&Float(5.0))
+        );
+        assert_eq!(
+            Some(Ordering::Less),
+            Integer(-5).compare_numeric(&Version(0))
+        );
+        assert_eq!(
+            Some(Ordering::Greater),
+            Float(5.5).compare_numeric(&UInteger128(5))
+        );
+        assert_eq!(None, Integer(5).compare_numeric(&String("5".into())));
+        assert_eq!(None, Null.compare_numeric(&Integer(0)));
+    }
+
+    #[test]
+    fn loose_eq() {
+        use DocumentValue::*;
+
+        assert!(Integer(5).loose_eq(&UInteger(5)));
+        assert!(Version(5).loose_eq(&Float(5.0)));
+        assert!(!Float(5.5).loose_eq(&Integer(5)));
+        assert!(Float(f64::NAN).loose_eq(&Float(f64::NAN)));
+        assert!(!String("5".into()).loose_eq(&Integer(5)));
+
+        let mut from_json = ValueMap::new();
+        from_json.insert("a", Array(vec![UInteger(1), Float(-2.0)]));
+        let mut from_cbor = ValueMap::new();
+        from_cbor.insert("a", Array(vec![Integer(1), Integer(-2)]));
+        assert!(Map(from_json.clone()).loose_eq(&Map(from_cbor.clone())));
+        assert_ne!(Map(from_json.clone()), Map(from_cbor.clone()));
+
+        from_cbor.insert("b", Null);
+        assert!(!Map(from_json).loose_eq(&Map(from_cbor)));
+    }
+
+    #[test]
+    fn normalize() {
+        use DocumentValue::*;
+
+        let value = Array(vec![
+            Integer(5),
+            Version(5),
+            Float(5.0),
+            Float(-0.0),
+            Float(-5.0),
+            Float(5.5),
+            Integer128(-1),
+            UInteger128(u64::MAX as u128 + 1),
+            Float(1e30),
+            Float(f64::INFINITY),
+        ]);
+
+        let normalized = value.clone().normalized();
+        assert_eq!(
+            Array(vec![
+                UInteger(5),
+                UInteger(5),
+                UInteger(5),
+                UInteger(0),
+                Integer(-5),
+                Float(5.5),
+                Integer(-1),
+                UInteger128(u64::MAX as u128 + 1),
+                UInteger128(1e30 as u128),
+                Float(f64::INFINITY),
+            ]),
+            normalized
+        );
+        assert!(value.loose_eq(&normalized));
+    }
+}
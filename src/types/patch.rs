@@ -0,0 +1,313 @@
+//! JSON Merge Patch ([RFC 7396](https://www.rfc-editor.org/rfc/rfc7396)) and JSON Patch
+//! ([RFC 6902](https://www.rfc-editor.org/rfc/rfc6902)) applied directly to [`DocumentValue`],
+//! so the binary variants of the values are preserved.
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::types::{DocumentValue, Path};
+
+/// Operation of JSON Patch. The paths are JSON Pointers
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: DocumentValue },
+    Remove { path: String },
+    Replace { path: String, value: DocumentValue },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: DocumentValue },
+}
+
+impl PatchOperation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PatchOperation::Add { .. } => "add",
+            PatchOperation::Remove { .. } => "remove",
+            PatchOperation::Replace { .. } => "replace",
+            PatchOperation::Move { .. } => "move",
+            PatchOperation::Copy { .. } => "copy",
+            PatchOperation::Test { .. } => "test",
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            PatchOperation::Add { path, .. }
+            | PatchOperation::Remove { path }
+            | PatchOperation::Replace { path, .. }
+            | PatchOperation::Move { path, .. }
+            | PatchOperation::Copy { path, .. }
+            | PatchOperation::Test { path, .. } => path,
+        }
+    }
+
+    fn apply(&self, target: &mut DocumentValue) -> Result<(), String> {
+        let pointer = self.path();
+        let path = Path::from_pointer(pointer).map_err(|e| e.to_string())?;
+
+        match self {
+            PatchOperation::Add { value, .. } => target.insert_at(pointer, &path, value.clone()),
+            PatchOperation::Remove { .. } => target.remove_at(pointer, &path).map(|_| ()),
+            PatchOperation::Replace { value, .. } => target
+                .get_at_mut(pointer, &path)
+                .map(|current| *current = value.clone()),
+            PatchOperation::Move { from, .. } => {
+                let from_path = Path::from_pointer(from).map_err(|e| e.to_string())?;
+                let is_proper_prefix = from_path.segments().len() < path.segments().len()
+                    && path.segments().starts_with(from_path.segments());
+                if is_proper_prefix {
+                    return Err(format!("can't move '{from}' into its own child"));
+                }
+                target
+                    .remove_at(from, &from_path)
+                    .and_then(|value| target.insert_at(pointer, &path, value))
+            }
+            PatchOperation::Copy { from, .. } => {
+                let from_path = Path::from_pointer(from).map_err(|e| e.to_string())?;
+                target
+                    .get_at(from, &from_path)
+                    .cloned()
+                    .and_then(|value| target.insert_at(pointer, &path, value))
+            }
+            PatchOperation::Test { value, .. } => {
+                let current = target.get_at(pointer, &path).map_err(|e| e.to_string())?;
+                if !current.loose_eq(value) {
+                    return Err(format!("expected {value:?}, got {current:?}"));
+                }
+                Ok(())
+            }
+        }
+        .map_err(|e| e.to_string())
+    }
+}
+
+impl DocumentValue {
+    /// Applies the JSON Patch operations in order. The patch is atomic: if any operation fails
+    /// the value is left unchanged. `test` compares numbers by their value, as the RFC requires
+    pub fn apply_patch(&mut self, operations: &[PatchOperation]) -> Result<(), Error> {
+        let mut patched = self.clone();
+        for (index, operation) in operations.iter().enumerate() {
+            operation
+                .apply(&mut patched)
+                .map_err(|reason| Error::PatchError {
+                    index,
+                    op: String::from(operation.name()),
+                    path: String::from(operation.path()),
+                    reason,
+                })?;
+        }
+        *self = patched;
+        Ok(())
+    }
+
+    /// Applies JSON Merge Patch: maps are merged recursively, `Null` removes the key and any
+    /// other value replaces the target
+    pub fn merge_patch(&mut self, patch: &DocumentValue) {
+        let patch_map = match patch {
+            DocumentValue::Map(map) => map,
+            _ => {
+                *self = patch.clone();
+                return;
+            }
+        };
+
+        if !matches!(self, DocumentValue::Map(_)) {
+            *self = DocumentValue::Map(Default::default());
+        }
+        if let DocumentValue::Map(target) = self {
+            for (key, value) in patch_map {
+                if value.is_null() {
+                    target.remove(key);
+                    continue;
+                }
+                if !target.contains_key(key) {
+                    target.insert(key.as_str(), DocumentValue::Null);
+                }
+                if let Some(current) = target.get_mut(key) {
+                    current.merge_patch(value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::value;
+
+    fn example() -> DocumentValue {
+        value!({
+            "$id": id([1_u8; 32]),
+            "name": "Cutie",
+            "tags": ["a", "b"],
+            "avatar": bytes(vec![1, 2]),
+            "nested": { "count": 1 },
+        })
+    }
+
+    #[test]
+    fn merge_patch() {
+        let mut value = example();
+        value.merge_patch(&value!({
+            "name": null,
+            "nested": { "count": 2, "new": id([2_u8; 32]) },
+            "tags": ["c"],
+            "extra": { "deep": bytes(vec![3]) },
+        }));
+
+        assert_eq!(
+            value!({
+                "$id": id([1_u8; 32]),
+                "tags": ["c"],
+                "avatar": bytes(vec![1, 2]),
+                "nested": { "count": 2, "new": id([2_u8; 32]) },
+                "extra": { "deep": bytes(vec![3]) },
+            }),
+            value
+        );
+
+        let mut value = example();
+        value.merge_patch(&value!("replaced"));
+        assert_eq!(value!("replaced"), value);
+    }
+
+    #[test]
+    fn apply_patch() {
+        let mut value = example();
+        value
+            .apply_patch(&[
+                PatchOperation::Add {
+                    path: String::from("/tags/1"),
+                    value: value!("inserted"),
+                },
+                PatchOperation::Add {
+                    path: String::from("/tags/-"),
+                    value: value!(bytes(vec![9])),
+                },
+                PatchOperation::Remove {
+                    path: String::from("/name"),
+                },
+                PatchOperation::Replace {
+                    path: String::from("/nested/count"),
+                    value: value!(2),
+                },
+                PatchOperation::Move {
+                    from: String::from("/avatar"),
+                    path: String::from("/nested/avatar"),
+                },
+                PatchOperation::Copy {
+                    from: String::from("/$id"),
+                    path: String::from("/owner"),
+                },
+                PatchOperation::Test {
+                    path: String::from("/nested/count"),
+                    value: value!(2.0),
+                },
+            ])
+            .expect("no error");
+
+        assert_eq!(
+            value!({
+                "$id": id([1_u8; 32]),
+                "owner": id([1_u8; 32]),
+                "tags": ["a", "inserted", "b", bytes(vec![9])],
+                "nested": { "count": 2, "avatar": bytes(vec![1, 2]) },
+            }),
+            value
+        );
+    }
+
+    #[test]
+    fn patch_is_atomic() {
+        let mut value = example();
+        let result = value.apply_patch(&[
+            PatchOperation::Remove {
+                path: String::from("/name"),
+            },
+            PatchOperation::Test {
+                path: String::from("/nested/count"),
+                value: value!(5),
+            },
+        ]);
+
+        assert!(matches!(
+            result,
+            Err(Error::PatchError { index: 1, ref op, ref path, .. }) if op == "test" && path == "/nested/count"
+        ));
+        assert_eq!(example(), value);
+    }
+
+    #[test]
+    fn errors_name_the_operation() {
+        let cases = [
+            (
+                PatchOperation::Add {
+                    path: String::from("/missing/key"),
+                    value: value!(1),
+                },
+                "add",
+            ),
+            (
+                PatchOperation::Add {
+                    path: String::from("/tags/5"),
+                    value: value!(1),
+                },
+                "add",
+            ),
+            (
+                PatchOperation::Remove {
+                    path: String::from("/missing"),
+                },
+                "remove",
+            ),
+            (
+                PatchOperation::Replace {
+                    path: String::from("/tags/2"),
+                    value: value!(1),
+                },
+                "replace",
+            ),
+            (
+                PatchOperation::Move {
+                    from: String::from("/nested"),
+                    path: String::from("/nested/child"),
+                },
+                "move",
+            ),
+            (
+                PatchOperation::Copy {
+                    from: String::from("/missing"),
+                    path: String::from("/copy"),
+                },
+                "copy",
+            ),
+        ];
+
+        for (operation, name) in cases {
+            let mut value = example();
+            let err = value.apply_patch(&[operation]).unwrap_err();
+            assert!(
+                matches!(err, Error::PatchError { ref op, .. } if op == name),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn deserialize_patch_document() {
+        let operations: Vec<PatchOperation> = serde_json::from_str(
+            r#"[
+                { "op": "replace", "path": "/name", "value": "Lucky" },
+                { "op": "move", "from": "/tags/0", "path": "/first" }
+            ]"#,
+        )
+        .expect("no error");
+
+        let mut value = example();
+        value.apply_patch(&operations).expect("no error");
+        assert_eq!(value!("Lucky"), value["name"]);
+        assert_eq!(value!("a"), value["first"]);
+        assert_eq!(value!(["b"]), value["tags"]);
+    }
+}
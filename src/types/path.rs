@@ -0,0 +1,595 @@
+use std::fmt::{Display, Write};
+
+use crate::error::Error;
+use crate::types::{DocumentValue, ValueMap};
+
+/// A single step of a [`Path`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Key(key) => f.write_str(key),
+            Self::Index(idx) => write!(f, "[{idx}]"),
+        }
+    }
+}
+
+/// Location of a value inside a [`DocumentValue`] tree. It can be parsed from the property
+/// path notation (`a.b[0].c`) or from a JSON Pointer (`/a/b/0/c`).
+///
+/// Segments parsed from a JSON Pointer are always keys; a key is treated as an index when
+/// the value it's applied to is an array, and `-` refers to the end of an array.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Parses the property path notation, e.g. `address.street` or `items[0].name`
+    pub fn parse(path: &str) -> Result<Self, Error> {
+        let mut segments = Vec::new();
+        let mut chars = path.chars().peekable();
+        let mut key = String::new();
+        // set when a segment has just been closed and a separator is required
+        let mut closed = false;
+
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    if key.is_empty() && !closed {
+                        return Err(Error::path(path, ".", "empty key"));
+                    }
+                    if !key.is_empty() {
+                        segments.push(PathSegment::Key(std::mem::take(&mut key)));
+                    }
+                    closed = false;
+                    if chars.peek().is_none() {
+                        return Err(Error::path(path, ".", "empty key"));
+                    }
+                }
+                '[' => {
+                    if !key.is_empty() {
+                        segments.push(PathSegment::Key(std::mem::take(&mut key)));
+                    }
+                    let mut digits = String::new();
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(d) => digits.push(d),
+                            None => {
+                                return Err(Error::path(path, format!("[{digits}"), "missing ']'"))
+                            }
+                        }
+                    }
+                    let idx = digits.parse::<usize>().map_err(|_| {
+                        Error::path(path, format!("[{digits}]"), "invalid array index")
+                    })?;
+                    segments.push(PathSegment::Index(idx));
+                    closed = true;
+                }
+                _ => {
+                    if closed {
+                        return Err(Error::path(
+                            path,
+                            c.to_string(),
+                            "expected '.' or '[' after ']'",
+                        ));
+                    }
+                    key.push(c);
+                }
+            }
+        }
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Parses a JSON Pointer (RFC 6901), e.g. `/address/street`
+    pub fn from_pointer(pointer: &str) -> Result<Self, Error> {
+        if pointer.is_empty() {
+            return Ok(Self::root());
+        }
+        let tokens = match pointer.strip_prefix('/') {
+            Some(tokens) => tokens,
+            None => return Err(Error::path(pointer, pointer, "pointer must start with '/'")),
+        };
+
+        let mut segments = Vec::new();
+        for token in tokens.split('/') {
+            if token.replace("~0", "").replace("~1", "").contains('~') {
+                return Err(Error::path(pointer, token, "invalid escape sequence"));
+            }
+            segments.push(PathSegment::Key(
+                token.replace("~1", "/").replace("~0", "~"),
+            ));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Formats the path as a JSON Pointer
+    pub fn to_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for segment in &self.segments {
+            pointer.push('/');
+            match segment {
+                PathSegment::Key(key) => {
+                    pointer.push_str(&key.replace('~', "~0").replace('/', "~1"))
+                }
+                PathSegment::Index(idx) => {
+                    let _ = write!(pointer, "{idx}");
+                }
+            }
+        }
+        pointer
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment)
+    }
+
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+
+    /// Returns a new path extended with the segment
+    pub fn join(&self, segment: PathSegment) -> Self {
+        let mut path = self.clone();
+        path.push(segment);
+        path
+    }
+
+    /// Creates [`Error::PathError`] for the last segment of the path
+    pub fn error(&self, reason: impl Into<String>) -> Error {
+        let segment = self
+            .segments
+            .last()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        Error::path(self.to_string(), segment, reason)
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 && matches!(segment, PathSegment::Key(_)) {
+                f.write_char('.')?;
+            }
+            write!(f, "{segment}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Vec<PathSegment>> for Path {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        Self { segments }
+    }
+}
+
+// How a segment applies to a concrete container
+enum Step<'a> {
+    Key(&'a str),
+    Index(usize),
+    Append,
+}
+
+fn step<'a>(value: &DocumentValue, segment: &'a PathSegment) -> Result<Step<'a>, &'static str> {
+    match (value, segment) {
+        (DocumentValue::Map(_), PathSegment::Key(key)) => Ok(Step::Key(key)),
+        (DocumentValue::Map(_), PathSegment::Index(_)) => Err("the value is a map, not an array"),
+        (DocumentValue::Array(_), PathSegment::Index(idx)) => Ok(Step::Index(*idx)),
+        (DocumentValue::Array(_), PathSegment::Key(key)) if key == "-" => Ok(Step::Append),
+        (DocumentValue::Array(_), PathSegment::Key(key)) => parse_index(key).map(Step::Index),
+        _ => Err("the value isn't a map or an array"),
+    }
+}
+
+// Like `step`, but an index equal to the array length means appending
+fn insert_step<'a>(
+    value: &DocumentValue,
+    segment: &'a PathSegment,
+) -> Result<Step<'a>, &'static str> {
+    match (step(value, segment)?, value) {
+        (Step::Index(idx), DocumentValue::Array(array)) if idx == array.len() => Ok(Step::Append),
+        (step, _) => Ok(step),
+    }
+}
+
+fn parse_index(token: &str) -> Result<usize, &'static str> {
+    let valid = !token.is_empty()
+        && token.bytes().all(|b| b.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+    if !valid {
+        return Err("invalid array index");
+    }
+    token.parse().map_err(|_| "invalid array index")
+}
+
+fn container_for(segment: &PathSegment) -> DocumentValue {
+    match segment {
+        PathSegment::Key(_) => DocumentValue::Map(ValueMap::new()),
+        PathSegment::Index(_) => DocumentValue::Array(Vec::new()),
+    }
+}
+
+impl DocumentValue {
+    /// Returns the value under the property path, e.g. `address.street` or `items[0].name`
+    pub fn get_path(&self, path: &str) -> Result<&DocumentValue, Error> {
+        self.get_at(path, &Path::parse(path)?)
+    }
+
+    pub fn get_path_mut(&mut self, path: &str) -> Result<&mut DocumentValue, Error> {
+        let parsed = Path::parse(path)?;
+        self.get_at_mut(path, &parsed)
+    }
+
+    /// Sets the value under the property path, creating missing maps and arrays on the way.
+    /// An array index equal to the array length appends the value
+    pub fn set_path(&mut self, path: &str, value: DocumentValue) -> Result<(), Error> {
+        let parsed = Path::parse(path)?;
+        self.set_at(path, &parsed, value)
+    }
+
+    /// Removes and returns the value under the property path
+    pub fn remove_path(&mut self, path: &str) -> Result<DocumentValue, Error> {
+        let parsed = Path::parse(path)?;
+        self.remove_at(path, &parsed)
+    }
+
+    /// Returns the value under the JSON Pointer, e.g. `/address/street`
+    pub fn get_pointer(&self, pointer: &str) -> Result<&DocumentValue, Error> {
+        self.get_at(pointer, &Path::from_pointer(pointer)?)
+    }
+
+    pub fn get_pointer_mut(&mut self, pointer: &str) -> Result<&mut DocumentValue, Error> {
+        let parsed = Path::from_pointer(pointer)?;
+        self.get_at_mut(pointer, &parsed)
+    }
+
+    /// Sets the value under the JSON Pointer, creating missing maps on the way. The `-`
+    /// token appends to an array
+    pub fn set_pointer(&mut self, pointer: &str, value: DocumentValue) -> Result<(), Error> {
+        let parsed = Path::from_pointer(pointer)?;
+        self.set_at(pointer, &parsed, value)
+    }
+
+    pub fn remove_pointer(&mut self, pointer: &str) -> Result<DocumentValue, Error> {
+        let parsed = Path::from_pointer(pointer)?;
+        self.remove_at(pointer, &parsed)
+    }
+
+    pub(crate) fn get_at(&self, input: &str, path: &Path) -> Result<&DocumentValue, Error> {
+        let mut current = self;
+        for segment in path.segments() {
+            let fail = |reason: &str| Error::path(input, segment.to_string(), reason);
+            current = match (current, step(current, segment).map_err(fail)?) {
+                (DocumentValue::Map(map), Step::Key(key)) => {
+                    map.get(key).ok_or_else(|| fail("key not found"))?
+                }
+                (DocumentValue::Array(array), Step::Index(idx)) => {
+                    array.get(idx).ok_or_else(|| fail("index out of bounds"))?
+                }
+                _ => return Err(fail("index out of bounds")),
+            };
+        }
+        Ok(current)
+    }
+
+    pub(crate) fn get_at_mut(
+        &mut self,
+        input: &str,
+        path: &Path,
+    ) -> Result<&mut DocumentValue, Error> {
+        self.lookup_mut(input, path)?
+    }
+
+    /// Like `get_at_mut`, but a missing key or index isn't an error
+    pub(crate) fn find_at_mut(
+        &mut self,
+        input: &str,
+        path: &Path,
+    ) -> Result<Option<&mut DocumentValue>, Error> {
+        Ok(self.lookup_mut(input, path)?.ok())
+    }
+
+    // The outer error is a path that doesn't fit the structure of the value, the inner one
+    // is a key or an index which doesn't exist
+    fn lookup_mut(
+        &mut self,
+        input: &str,
+        path: &Path,
+    ) -> Result<Result<&mut DocumentValue, Error>, Error> {
+        let mut current = self;
+        for segment in path.segments() {
+            let fail = |reason: &str| Error::path(input, segment.to_string(), reason);
+            let step = step(current, segment).map_err(fail)?;
+            let next = match (current, step) {
+                (DocumentValue::Map(map), Step::Key(key)) => {
+                    map.get_mut(key).ok_or_else(|| fail("key not found"))
+                }
+                (DocumentValue::Array(array), Step::Index(idx)) => array
+                    .get_mut(idx)
+                    .ok_or_else(|| fail("index out of bounds")),
+                _ => Err(fail("index out of bounds")),
+            };
+            current = match next {
+                Ok(next) => next,
+                Err(e) => return Ok(Err(e)),
+            };
+        }
+        Ok(Ok(current))
+    }
+
+    pub(crate) fn set_at(
+        &mut self,
+        input: &str,
+        path: &Path,
+        value: DocumentValue,
+    ) -> Result<(), Error> {
+        let (last, parents) = match path.segments().split_last() {
+            Some(split) => split,
+            None => {
+                *self = value;
+                return Ok(());
+            }
+        };
+
+        let mut current = self;
+        for (i, segment) in parents.iter().enumerate() {
+            let fail = |reason: &str| Error::path(input, segment.to_string(), reason);
+            if matches!(current, DocumentValue::Null) {
+                *current = container_for(segment);
+            }
+            let next = parents.get(i + 1).unwrap_or(last);
+            let step = insert_step(current, segment).map_err(fail)?;
+            current = match (current, step) {
+                (DocumentValue::Map(map), Step::Key(key)) => {
+                    if !map.contains_key(key) {
+                        map.insert(key, container_for(next));
+                    }
+                    map.get_mut(key).ok_or_else(|| fail("key not found"))?
+                }
+                (DocumentValue::Array(array), Step::Append) => {
+                    array.push(container_for(next));
+                    array
+                        .last_mut()
+                        .ok_or_else(|| fail("index out of bounds"))?
+                }
+                (DocumentValue::Array(array), Step::Index(idx)) => array
+                    .get_mut(idx)
+                    .ok_or_else(|| fail("index out of bounds"))?,
+                _ => return Err(fail("index out of bounds")),
+            };
+        }
+
+        let fail = |reason: &str| Error::path(input, last.to_string(), reason);
+        if matches!(current, DocumentValue::Null) {
+            *current = container_for(last);
+        }
+        match (insert_step(current, last).map_err(fail)?, current) {
+            (Step::Key(key), DocumentValue::Map(map)) => {
+                map.insert(key, value);
+            }
+            (Step::Append, DocumentValue::Array(array)) => array.push(value),
+            (Step::Index(idx), DocumentValue::Array(array)) => match array.get_mut(idx) {
+                Some(slot) => *slot = value,
+                None => return Err(fail("index out of bounds")),
+            },
+            _ => return Err(fail("index out of bounds")),
+        }
+        Ok(())
+    }
+
+    /// Inserts the value the way JSON Patch `add` does: the parent must exist, an array index
+    /// shifts the following elements and a map key is replaced
+    pub(crate) fn insert_at(
+        &mut self,
+        input: &str,
+        path: &Path,
+        value: DocumentValue,
+    ) -> Result<(), Error> {
+        let (last, parents) = match path.segments().split_last() {
+            Some(split) => split,
+            None => {
+                *self = value;
+                return Ok(());
+            }
+        };
+
+        let parent = self.get_at_mut(input, &Path::from(parents.to_vec()))?;
+        let fail = |reason: &str| Error::path(input, last.to_string(), reason);
+        match (insert_step(parent, last).map_err(fail)?, parent) {
+            (Step::Key(key), DocumentValue::Map(map)) => {
+                map.insert(key, value);
+            }
+            (Step::Append, DocumentValue::Array(array)) => array.push(value),
+            (Step::Index(idx), DocumentValue::Array(array)) if idx < array.len() => {
+                array.insert(idx, value)
+            }
+            _ => return Err(fail("index out of bounds")),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove_at(&mut self, input: &str, path: &Path) -> Result<DocumentValue, Error> {
+        let (last, parents) = match path.segments().split_last() {
+            Some(split) => split,
+            None => return Ok(std::mem::take(self)),
+        };
+
+        let parent = self.get_at_mut(input, &Path::from(parents.to_vec()))?;
+        let fail = |reason: &str| Error::path(input, last.to_string(), reason);
+        match (step(parent, last).map_err(fail)?, parent) {
+            (Step::Key(key), DocumentValue::Map(map)) => {
+                map.remove(key).ok_or_else(|| fail("key not found"))
+            }
+            (Step::Index(idx), DocumentValue::Array(array)) if idx < array.len() => {
+                Ok(array.remove(idx))
+            }
+            _ => Err(fail("index out of bounds")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{Path, PathSegment};
+    use crate::error::Error;
+    use crate::types::{DocumentValue, Identifier};
+
+    fn example() -> DocumentValue {
+        json!({
+            "address": {
+                "street": "Main",
+                "numbers": [1, 2, { "flat": "a/b" }]
+            },
+            "tilde~key": true
+        })
+        .try_into()
+        .expect("no error")
+    }
+
+    #[test]
+    fn parse_property_paths() {
+        let path = Path::parse("a.b[0][1].c").expect("no error");
+        assert_eq!(
+            &[
+                PathSegment::Key(String::from("a")),
+                PathSegment::Key(String::from("b")),
+                PathSegment::Index(0),
+                PathSegment::Index(1),
+                PathSegment::Key(String::from("c")),
+            ],
+            path.segments()
+        );
+        assert_eq!("a.b[0][1].c", path.to_string());
+        assert_eq!("/a/b/0/1/c", path.to_pointer());
+
+        for invalid in ["a..b", ".a", "a.", "a[x]", "a[1", "a[0]b"] {
+            assert!(
+                matches!(Path::parse(invalid), Err(Error::PathError { .. })),
+                "{invalid} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn get_by_path_and_pointer() {
+        let value = example();
+        assert_eq!(
+            &DocumentValue::String(String::from("Main")),
+            value.get_path("address.street").expect("no error")
+        );
+        assert_eq!(
+            &DocumentValue::String(String::from("a/b")),
+            value.get_path("address.numbers[2].flat").expect("no error")
+        );
+        assert_eq!(
+            &DocumentValue::UInteger(2),
+            value.get_pointer("/address/numbers/1").expect("no error")
+        );
+        assert_eq!(
+            &DocumentValue::Bool(true),
+            value.get_pointer("/tilde~0key").expect("no error")
+        );
+        assert_eq!(&value, value.get_pointer("").expect("no error"));
+    }
+
+    #[test]
+    fn errors_name_failing_segment() {
+        let value = example();
+
+        let err = value.get_path("address.city.name").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::PathError { ref path, ref segment, .. } if path == "address.city.name" && segment == "city"
+        ));
+
+        let err = value.get_path("address.numbers[5]").unwrap_err();
+        assert!(matches!(err, Error::PathError { ref segment, .. } if segment == "[5]"));
+
+        let err = value.get_pointer("/address/numbers/01").unwrap_err();
+        assert!(matches!(err, Error::PathError { ref segment, .. } if segment == "01"));
+
+        let err = value.get_path("address.street.name").unwrap_err();
+        assert!(matches!(err, Error::PathError { ref segment, .. } if segment == "name"));
+    }
+
+    #[test]
+    fn set_creates_intermediate_values() {
+        let mut value = DocumentValue::Null;
+        value
+            .set_path("a.b[0].c", DocumentValue::UInteger(1))
+            .expect("no error");
+        value
+            .set_path("a.b[1]", DocumentValue::UInteger(2))
+            .expect("no error");
+        value
+            .set_pointer("/a/d/e", DocumentValue::Bool(false))
+            .expect("no error");
+        value
+            .set_pointer("/a/b/-", DocumentValue::UInteger(3))
+            .expect("no error");
+
+        let expected: DocumentValue = json!({
+            "a": { "b": [{ "c": 1 }, 2, 3], "d": { "e": false } }
+        })
+        .try_into()
+        .expect("no error");
+        assert_eq!(expected, value);
+
+        value
+            .set_path("a.b[0]", DocumentValue::Identifier(Identifier::default()))
+            .expect("no error");
+        assert!(matches!(
+            value.get_path("a.b[0]"),
+            Ok(DocumentValue::Identifier(_))
+        ));
+
+        assert!(value.set_path("a.b[7]", DocumentValue::Null).is_err());
+        assert!(value.set_path("a.d.e.f", DocumentValue::Null).is_err());
+    }
+
+    #[test]
+    fn get_mut_and_remove() {
+        let mut value = example();
+        *value.get_path_mut("address.street").expect("no error") =
+            DocumentValue::String(String::from("High"));
+        assert_eq!(
+            DocumentValue::String(String::from("High")),
+            value["address"]["street"]
+        );
+
+        let removed = value
+            .remove_pointer("/address/numbers/0")
+            .expect("no error");
+        assert_eq!(DocumentValue::UInteger(1), removed);
+        assert_eq!(DocumentValue::UInteger(2), value["address"]["numbers"][0]);
+
+        value.remove_path("address.street").expect("no error");
+        assert!(value.get_path("address.street").is_err());
+        assert!(value.remove_path("address.street").is_err());
+    }
+}
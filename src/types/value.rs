@@ -1,44 +1,38 @@
-use std::{
-    cmp::Ordering,
-    collections::HashMap,
-    ops::{Index, IndexMut},
-};
+use std::ops::{Index, IndexMut};
 
 use anyhow::Context;
 use itertools::Itertools;
 use serde::{
     de::{MapAccess, SeqAccess, Visitor},
-    ser::{SerializeMap, SerializeSeq},
+    ser::SerializeSeq,
     Deserialize, Serialize,
 };
 
 use crate::{
     tri,
-    types::{Bytes, Identifier, StaticBytes},
+    types::{BinaryKind, Bytes, Identifier, Location, Path, StaticBytes, ValueMap, Walk},
+    Error,
 };
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default)]
 pub enum DocumentValue {
     Bool(bool),
     String(String),
     Float(f64),
     Integer(i64),
     UInteger(u64),
+    Integer128(i128),
+    UInteger128(u128),
     Version(u32),
-    Map(HashMap<String, DocumentValue>),
+    Map(ValueMap),
     Array(Vec<DocumentValue>),
     Identifier(Identifier),
     Bytes(Bytes),
     StaticBytes(StaticBytes),
+    #[default]
     Null,
 }
 
-impl Default for DocumentValue {
-    fn default() -> Self {
-        DocumentValue::Null
-    }
-}
-
 impl Serialize for DocumentValue {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -52,6 +46,8 @@ impl Serialize for DocumentValue {
             Self::Float(f) => serializer.serialize_f64(*f),
             Self::Integer(i) => serializer.serialize_i64(*i),
             Self::UInteger(u) => serializer.serialize_u64(*u),
+            Self::Integer128(i) => serializer.serialize_i128(*i),
+            Self::UInteger128(u) => serializer.serialize_u128(*u),
             Self::Version(v) => serializer.serialize_u32(*v),
             Self::Identifier(id) => Identifier::serialize(id, serializer),
             Self::Array(array) => {
@@ -62,26 +58,8 @@ impl Serialize for DocumentValue {
                 seq.end()
             }
 
-            Self::Map(map) => {
-                let mut m = serializer.serialize_map(Some(map.len()))?;
-                let sorted = map.iter().sorted_by(|a, b| {
-                    let key_a = a.0.as_bytes();
-                    let key_b = b.0.as_bytes();
-
-                    let len_comparison = key_a.len().cmp(&key_b.len());
-
-                    match len_comparison {
-                        Ordering::Less => Ordering::Less,
-                        Ordering::Equal => key_a.cmp(key_b),
-                        Ordering::Greater => Ordering::Greater,
-                    }
-                });
-
-                for (key, value) in sorted {
-                    m.serialize_entry(&key, &value)?;
-                }
-                m.end()
-            }
+            // the map keeps keys in the canonical order
+            Self::Map(map) => map.serialize(serializer),
             Self::Null => serializer.serialize_none(),
         }
     }
@@ -109,6 +87,8 @@ impl<'de> Deserialize<'de> for DocumentValue {
                 Ok(DocumentValue::Bool(v))
             }
 
+            // signed input is stored as `Integer` and unsigned as `UInteger`. The 128-bit
+            // variants are used only when the value doesn't fit into 64 bits
             fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
             where
                 E: serde::de::Error,
@@ -116,7 +96,35 @@ impl<'de> Deserialize<'de> for DocumentValue {
                 Ok(DocumentValue::Integer(v))
             }
 
-            #[cfg(any(feature = "std", feature = "alloc"))]
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(DocumentValue::UInteger(v))
+            }
+
+            fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(DocumentValue::from_i128(v))
+            }
+
+            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(DocumentValue::from_u128(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(DocumentValue::Float(v))
+            }
+
+            #[cfg(feature = "alloc")]
             #[inline]
             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
             where
@@ -125,7 +133,7 @@ impl<'de> Deserialize<'de> for DocumentValue {
                 self.visit_string(String::from(value))
             }
 
-            #[cfg(any(feature = "std", feature = "alloc"))]
+            #[cfg(feature = "alloc")]
             fn visit_string<E>(self, value: String) -> Result<Self::Value, E> {
                 Ok(DocumentValue::String(value))
             }
@@ -164,19 +172,12 @@ impl<'de> Deserialize<'de> for DocumentValue {
                 Ok(DocumentValue::Array(vec))
             }
 
-            #[cfg(any(feature = "std", feature = "alloc"))]
-            fn visit_map<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
+            #[cfg(feature = "alloc")]
+            fn visit_map<V>(self, visitor: V) -> Result<Self::Value, V::Error>
             where
                 V: MapAccess<'de>,
             {
-                let mut map: HashMap<String, DocumentValue> =
-                    HashMap::with_capacity(visitor.size_hint().unwrap_or(0));
-
-                while let Some((key, value)) = visitor.next_entry()? {
-                    map.insert(key, value);
-                }
-
-                Ok(DocumentValue::Map(map))
+                ValueMap::from_map_access(visitor).map(DocumentValue::Map)
             }
         }
         deserializer.deserialize_any(ValueVisitor)
@@ -184,46 +185,53 @@ impl<'de> Deserialize<'de> for DocumentValue {
 }
 
 impl DocumentValue {
+    /// Creates [`DocumentValue::Integer`] if the value fits into `i64`, otherwise
+    /// [`DocumentValue::Integer128`]
+    pub fn from_i128(v: i128) -> DocumentValue {
+        match i64::try_from(v) {
+            Ok(v) => DocumentValue::Integer(v),
+            Err(_) => DocumentValue::Integer128(v),
+        }
+    }
+
+    /// Creates [`DocumentValue::UInteger`] if the value fits into `u64`, otherwise
+    /// [`DocumentValue::UInteger128`]
+    pub fn from_u128(v: u128) -> DocumentValue {
+        match u64::try_from(v) {
+            Ok(v) => DocumentValue::UInteger(v),
+            Err(_) => DocumentValue::UInteger128(v),
+        }
+    }
+
     // Replaces bytes types with [`DocumentValue::Array`], providing an array representation
     // instead of a String representation in cases where a human-readable Serializer is used.
     pub fn bytes_as_arrays(mut self) -> DocumentValue {
-        let mut to_walk: Vec<&mut DocumentValue> = vec![&mut self];
-
-        while let Some(value) = to_walk.pop() {
-            match value {
-                Self::Array(ref mut arr) => {
-                    for v in arr.iter_mut() {
-                        if v.is_container() {
-                            to_walk.push(v);
-                            continue;
-                        }
-                        if v.is_storing_bytes() {
-                            Self::replace_bytes_with_array(v);
-                        }
-                    }
-                }
-
-                Self::Map(ref mut map) => {
-                    for (_, v) in map.iter_mut() {
-                        if v.is_container() {
-                            to_walk.push(v);
-                            continue;
-                        }
-                        if v.is_storing_bytes() {
-                            Self::replace_bytes_with_array(v);
-                        }
-                    }
-                }
-
-                Self::Bytes(_) | Self::StaticBytes(_) | Self::Identifier(_) => {
-                    Self::replace_bytes_with_array(value);
-                }
+        self.walk_mut(&mut |_: &Location, value: &mut DocumentValue| {
+            if value.is_storing_bytes() {
+                Self::replace_bytes_with_array(value);
+                return Walk::SkipChildren;
+            }
+            Walk::Continue
+        });
+        self
+    }
 
-                _ => {}
+    // The inverse of `bytes_as_arrays`. Converts the integer arrays found under
+    // the property paths into binary variants, checking that every element is a byte and that
+    // identifiers are 32 bytes long. Paths which don't exist in the value are skipped, while
+    // the ones that don't fit its structure, e.g. going through a scalar, are errors.
+    pub fn arrays_as_bytes<'a>(
+        mut self,
+        paths: impl IntoIterator<Item = (&'a str, BinaryKind)>,
+    ) -> Result<DocumentValue, Error> {
+        for (path, kind) in paths {
+            let parsed = Path::parse(path)?;
+            if let Some(value) = self.find_at_mut(path, &parsed)? {
+                let owned = std::mem::take(value);
+                *value = kind.convert(owned, false, &parsed)?;
             }
         }
-
-        self
+        Ok(self)
     }
 
     pub fn get<'a, I: Into<DashValueIndex<'a>>>(&self, idx: I) -> Option<&DocumentValue> {
@@ -259,6 +267,26 @@ impl DocumentValue {
         }
     }
 
+    /// Returns the name of the variant, used in error messages
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Self::Bool(_) => "Bool",
+            Self::String(_) => "String",
+            Self::Float(_) => "Float",
+            Self::Integer(_) => "Integer",
+            Self::UInteger(_) => "UInteger",
+            Self::Integer128(_) => "Integer128",
+            Self::UInteger128(_) => "UInteger128",
+            Self::Version(_) => "Version",
+            Self::Map(_) => "Map",
+            Self::Array(_) => "Array",
+            Self::Identifier(_) => "Identifier",
+            Self::Bytes(_) => "Bytes",
+            Self::StaticBytes(_) => "StaticBytes",
+            Self::Null => "Null",
+        }
+    }
+
     pub fn is_container(&mut self) -> bool {
         matches!(self, Self::Array(_) | Self::Map(_))
     }
@@ -270,7 +298,7 @@ impl DocumentValue {
         )
     }
 
-    fn replace_bytes_with_array(value: &mut DocumentValue) {
+    pub(crate) fn replace_bytes_with_array(value: &mut DocumentValue) {
         let owned = std::mem::take(value);
         match owned {
             Self::Identifier(id) => {
@@ -332,7 +360,7 @@ where
                 _ => panic!("document value isn't a array"),
             },
             DashValueIndex::String(key) => match self {
-                DocumentValue::Map(map) => map.get(key).unwrap(),
+                DocumentValue::Map(map) => &map[key],
                 _ => panic!("document isn't a  map"),
             },
         }
@@ -351,7 +379,7 @@ where
                 _ => panic!("document value isn't a array"),
             },
             DashValueIndex::String(key) => match self {
-                DocumentValue::Map(map) => map.get_mut(key).unwrap(),
+                DocumentValue::Map(map) => &mut map[key],
                 _ => panic!("document isn't a  map"),
             },
         }
@@ -371,9 +399,10 @@ impl TryFrom<serde_json::Value> for DocumentValue {
 
 #[cfg(test)]
 mod test {
-    use crate::prelude::{Bytes, Identifier, StaticBytes};
+    use crate::prelude::{BinaryKind, Bytes, Error, Identifier};
 
     use super::DocumentValue;
+    use serde::Serialize;
     use serde_json::json;
 
     #[test]
@@ -394,7 +423,7 @@ mod test {
             dash_value["alpha"]["bravo"][0]
         );
 
-        dash_value["alpha"]["bravo"] = DocumentValue::Identifier(Identifier::from(vec![1_u8; 32]));
+        dash_value["alpha"]["bravo"] = DocumentValue::Identifier(Identifier::from([1_u8; 32]));
         assert!(matches!(
             dash_value["alpha"]["bravo"],
             DocumentValue::Identifier(_)
@@ -421,8 +450,7 @@ mod test {
         .try_into()
         .expect("no error");
 
-        dash_value["alpha"]["bravo"][0] =
-            DocumentValue::Identifier(Identifier::from(vec![1_u8; 32]));
+        dash_value["alpha"]["bravo"][0] = DocumentValue::Identifier(Identifier::from([1_u8; 32]));
         dash_value["alpha"]["charlie"] = DocumentValue::Bytes(Bytes(vec![2_u8; 32]));
 
         let replaced = dash_value.bytes_as_arrays();
@@ -439,4 +467,141 @@ mod test {
             DocumentValue::String(_)
         ));
     }
+
+    #[test]
+    fn numbers_from_json() {
+        let value: DocumentValue = serde_json::from_str(
+            r#"{ "negative": -5, "positive": 5, "max": 18446744073709551615, "float": 1.5 }"#,
+        )
+        .expect("no error");
+
+        assert_eq!(DocumentValue::Integer(-5), value["negative"]);
+        assert_eq!(DocumentValue::UInteger(5), value["positive"]);
+        assert_eq!(DocumentValue::UInteger(u64::MAX), value["max"]);
+        assert_eq!(DocumentValue::Float(1.5), value["float"]);
+    }
+
+    #[test]
+    fn numbers_round_trip_cbor() {
+        for value in [
+            DocumentValue::Integer(i64::MIN),
+            DocumentValue::UInteger(u64::MAX),
+            DocumentValue::Float(-0.25),
+        ] {
+            let bytes = serde_cbor::to_vec(&value).expect("no error");
+            let decoded: DocumentValue = serde_cbor::from_slice(&bytes).expect("no error");
+            assert_eq!(value, decoded);
+        }
+    }
+
+    #[test]
+    fn numbers_128_bit() {
+        let big = u64::MAX as u128 + 1;
+        assert_eq!(
+            DocumentValue::UInteger128(big),
+            DocumentValue::from_u128(big)
+        );
+        assert_eq!(DocumentValue::UInteger(7), DocumentValue::from_u128(7));
+        assert_eq!(DocumentValue::Integer(-7), DocumentValue::from_i128(-7));
+        assert_eq!(
+            DocumentValue::Integer128(i128::MIN),
+            DocumentValue::from_i128(i128::MIN)
+        );
+
+        for value in [
+            DocumentValue::UInteger128(u128::MAX),
+            DocumentValue::Integer128(i128::MIN),
+        ] {
+            let decoded: DocumentValue =
+                crate::deserializer::from_dash_value(value.clone()).expect("no error");
+            assert_eq!(value, decoded);
+        }
+
+        let serialized = big
+            .serialize(crate::serializer::ToDashValue::default())
+            .expect("no error");
+        assert_eq!(DocumentValue::UInteger128(big), serialized);
+
+        let json = serde_json::to_string(&DocumentValue::UInteger128(big)).expect("no error");
+        assert_eq!("18446744073709551616", json);
+    }
+
+    #[test]
+    fn replace_arrays_with_bytes_types() {
+        let mut dash_value: DocumentValue = json!({
+            "alpha" : {
+                "bravo" : [ "bravo_value" ],
+                "epsilon": "string_value",
+            }
+        })
+        .try_into()
+        .expect("no error");
+        dash_value["alpha"]["bravo"][0] = DocumentValue::Identifier(Identifier::from([1_u8; 32]));
+        dash_value
+            .set_path("alpha.charlie", DocumentValue::Bytes(Bytes(vec![2_u8; 5])))
+            .expect("no error");
+        let original = dash_value;
+
+        let restored = original
+            .clone()
+            .bytes_as_arrays()
+            .arrays_as_bytes([
+                ("alpha.bravo[0]", BinaryKind::Identifier),
+                ("alpha.charlie", BinaryKind::Bytes),
+                ("alpha.missing", BinaryKind::Bytes),
+            ])
+            .expect("no error");
+        assert_eq!(original, restored);
+    }
+
+    #[test]
+    fn arrays_as_bytes_validates_data() {
+        let value: DocumentValue = json!({ "id": [1, 2, 3], "data": [1, 256], "name": "x" })
+            .try_into()
+            .expect("no error");
+
+        let err = value
+            .clone()
+            .arrays_as_bytes([("id", BinaryKind::Identifier)])
+            .unwrap_err();
+        assert!(matches!(err, Error::PathError { ref path, .. } if path == "id"));
+
+        let err = value
+            .clone()
+            .arrays_as_bytes([("data", BinaryKind::Bytes)])
+            .unwrap_err();
+        assert!(matches!(err, Error::PathError { ref path, .. } if path == "data[1]"));
+
+        let err = value
+            .arrays_as_bytes([("name", BinaryKind::Bytes)])
+            .unwrap_err();
+        assert!(matches!(err, Error::PathError { ref path, .. } if path == "name"));
+    }
+
+    #[test]
+    fn arrays_as_bytes_skips_only_missing_paths() {
+        let value: DocumentValue = json!({ "name": "x", "nested": { "data": [1] } })
+            .try_into()
+            .expect("no error");
+
+        let unchanged = value
+            .clone()
+            .arrays_as_bytes([
+                ("missing", BinaryKind::Bytes),
+                ("nested.other", BinaryKind::Bytes),
+            ])
+            .expect("no error");
+        assert_eq!(value, unchanged);
+
+        for path in ["name.data", "nested[0]"] {
+            let err = value
+                .clone()
+                .arrays_as_bytes([(path, BinaryKind::Bytes)])
+                .unwrap_err();
+            assert!(
+                matches!(err, Error::PathError { path: ref p, .. } if p == path),
+                "{err}"
+            );
+        }
+    }
 }
@@ -0,0 +1,429 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::Debug,
+    ops::{Index, IndexMut},
+};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Serialize,
+};
+
+use crate::types::DocumentValue;
+
+/// Map of [`DocumentValue`]s which keeps its keys in the DPP canonical order: shorter keys
+/// first, then bytewise. Iteration, serialization and `Debug` follow that order
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct ValueMap {
+    entries: Vec<(String, DocumentValue)>,
+}
+
+/// Compares the keys using the DPP canonical order
+pub(crate) fn canonical_key_order(a: &str, b: &str) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.as_bytes().cmp(b.as_bytes()))
+}
+
+// Entry by entry, the keys in the canonical order and then the values. A map which is a
+// prefix of the other one is smaller
+impl Ord for ValueMap {
+    fn cmp(&self, other: &Self) -> Ordering {
+        for ((a_key, a_value), (b_key, b_value)) in self.entries.iter().zip(&other.entries) {
+            let by_entry = canonical_key_order(a_key, b_key).then_with(|| a_value.cmp(b_value));
+            if by_entry.is_ne() {
+                return by_entry;
+            }
+        }
+        self.len().cmp(&other.len())
+    }
+}
+
+impl PartialOrd for ValueMap {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ValueMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+
+    fn position(&self, key: &str) -> Result<usize, usize> {
+        self.entries
+            .binary_search_by(|(k, _)| canonical_key_order(k, key))
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.position(key).is_ok()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&DocumentValue> {
+        let idx = self.position(key).ok()?;
+        Some(&self.entries[idx].1)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut DocumentValue> {
+        let idx = self.position(key).ok()?;
+        Some(&mut self.entries[idx].1)
+    }
+
+    /// Inserts the value, returning the previous value stored under the key
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        value: DocumentValue,
+    ) -> Option<DocumentValue> {
+        let key = key.into();
+        match self.position(&key) {
+            Ok(idx) => Some(std::mem::replace(&mut self.entries[idx].1, value)),
+            Err(idx) => {
+                self.entries.insert(idx, (key, value));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<DocumentValue> {
+        let idx = self.position(key).ok()?;
+        Some(self.entries.remove(idx).1)
+    }
+
+    pub fn retain(&mut self, mut f: impl FnMut(&str, &mut DocumentValue) -> bool) {
+        self.entries.retain_mut(|(k, v)| f(k, v))
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter(self.entries.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut(self.entries.iter_mut())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &DocumentValue> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut DocumentValue> {
+        self.entries.iter_mut().map(|(_, v)| v)
+    }
+}
+
+impl Debug for ValueMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl Index<&str> for ValueMap {
+    type Output = DocumentValue;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        self.get(key)
+            .unwrap_or_else(|| panic!("key '{key}' not found in the map"))
+    }
+}
+
+impl IndexMut<&str> for ValueMap {
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        self.get_mut(key)
+            .unwrap_or_else(|| panic!("key '{key}' not found in the map"))
+    }
+}
+
+impl<K: Into<String>> FromIterator<(K, DocumentValue)> for ValueMap {
+    fn from_iter<T: IntoIterator<Item = (K, DocumentValue)>>(iter: T) -> Self {
+        let mut map = ValueMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+// Appends the entries and sorts once, instead of inserting them one by one: building a map
+// from unordered input stays O(n log n). As with `insert`, the last value of a key wins
+impl<K: Into<String>> Extend<(K, DocumentValue)> for ValueMap {
+    fn extend<T: IntoIterator<Item = (K, DocumentValue)>>(&mut self, iter: T) {
+        self.entries
+            .extend(iter.into_iter().map(|(k, v)| (k.into(), v)));
+        self.entries
+            .sort_by(|(a, _), (b, _)| canonical_key_order(a, b));
+        self.entries.dedup_by(|(later_key, later), (key, value)| {
+            if later_key != key {
+                return false;
+            }
+            std::mem::swap(later, value);
+            true
+        });
+    }
+}
+
+impl From<HashMap<String, DocumentValue>> for ValueMap {
+    fn from(map: HashMap<String, DocumentValue>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl Serialize for ValueMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for ValueMap {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ValueMapVisitor;
+
+        impl<'de> Visitor<'de> for ValueMapVisitor {
+            type Value = ValueMap;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A>(self, access: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                ValueMap::from_map_access(access)
+            }
+        }
+
+        deserializer.deserialize_map(ValueMapVisitor)
+    }
+}
+
+impl ValueMap {
+    // Collects the entries before sorting them, see `Extend`
+    pub(crate) fn from_map_access<'de, A>(mut access: A) -> Result<ValueMap, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some(entry) = access.next_entry::<String, DocumentValue>()? {
+            entries.push(entry);
+        }
+        Ok(entries.into_iter().collect())
+    }
+}
+
+pub struct Iter<'a>(std::slice::Iter<'a, (String, DocumentValue)>);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a DocumentValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, v)| (k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+pub struct IterMut<'a>(std::slice::IterMut<'a, (String, DocumentValue)>);
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a String, &'a mut DocumentValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, v)| (&*k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for IterMut<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(k, v)| (&*k, v))
+    }
+}
+
+impl<'a> ExactSizeIterator for IterMut<'a> {}
+
+pub struct IntoIter(std::vec::IntoIter<(String, DocumentValue)>);
+
+impl Iterator for IntoIter {
+    type Item = (String, DocumentValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl IntoIterator for ValueMap {
+    type Item = (String, DocumentValue);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.entries.into_iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a ValueMap {
+    type Item = (&'a String, &'a DocumentValue);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut ValueMap {
+    type Item = (&'a String, &'a mut DocumentValue);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ValueMap;
+    use crate::types::DocumentValue;
+
+    #[test]
+    fn keys_are_kept_in_canonical_order() {
+        let mut map = ValueMap::new();
+        for key in ["charlie", "b", "alpha", "a", "$id", "$type"] {
+            map.insert(key, DocumentValue::Null);
+        }
+
+        let keys: Vec<&str> = map.keys().map(String::as_str).collect();
+        assert_eq!(vec!["a", "b", "$id", "$type", "alpha", "charlie"], keys);
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = ValueMap::new();
+        assert_eq!(None, map.insert("alpha", DocumentValue::UInteger(1)));
+        assert_eq!(
+            Some(DocumentValue::UInteger(1)),
+            map.insert("alpha", DocumentValue::UInteger(2))
+        );
+        assert_eq!(1, map.len());
+        assert_eq!(Some(&DocumentValue::UInteger(2)), map.get("alpha"));
+
+        if let Some(value) = map.get_mut("alpha") {
+            *value = DocumentValue::Bool(true);
+        }
+        assert_eq!(DocumentValue::Bool(true), map["alpha"]);
+
+        assert!(map.contains_key("alpha"));
+        assert_eq!(Some(DocumentValue::Bool(true)), map.remove("alpha"));
+        assert_eq!(None, map.remove("alpha"));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn debug_output_is_stable() {
+        let forward: ValueMap = [("b", DocumentValue::Null), ("a", DocumentValue::Null)]
+            .into_iter()
+            .collect();
+        let backward: ValueMap = [("a", DocumentValue::Null), ("b", DocumentValue::Null)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(forward, backward);
+        assert_eq!(r#"{"a": Null, "b": Null}"#, format!("{:?}", forward));
+        assert_eq!(format!("{:?}", forward), format!("{:?}", backward));
+    }
+
+    #[test]
+    fn collect_sorts_and_keeps_the_last_value() {
+        let map: ValueMap = [
+            ("charlie", DocumentValue::UInteger(1)),
+            ("b", DocumentValue::UInteger(2)),
+            ("charlie", DocumentValue::UInteger(3)),
+            ("a", DocumentValue::UInteger(4)),
+        ]
+        .into_iter()
+        .collect();
+
+        let entries: Vec<(&str, &DocumentValue)> =
+            map.iter().map(|(k, v)| (k.as_str(), v)).collect();
+        assert_eq!(
+            vec![
+                ("a", &DocumentValue::UInteger(4)),
+                ("b", &DocumentValue::UInteger(2)),
+                ("charlie", &DocumentValue::UInteger(3)),
+            ],
+            entries
+        );
+
+        let mut map = map;
+        map.extend([("b", DocumentValue::Null), ("alpha", DocumentValue::Null)]);
+        assert_eq!(DocumentValue::Null, map["b"]);
+        assert_eq!(4, map.len());
+    }
+
+    #[test]
+    fn collect_many_unordered_keys() {
+        let map: ValueMap = (0..100_000)
+            .rev()
+            .map(|i| (i.to_string(), DocumentValue::UInteger(i)))
+            .collect();
+
+        assert_eq!(100_000, map.len());
+        assert_eq!(DocumentValue::UInteger(42), map["42"]);
+        assert_eq!(Some(&String::from("0")), map.keys().next());
+    }
+}
@@ -0,0 +1,316 @@
+//! Non-recursive traversal of [`DocumentValue`] trees. The values are visited depth-first,
+//! parents before their children, and maps in the canonical key order.
+use crate::types::{DocumentValue, Path, PathSegment};
+
+/// What to do after visiting a value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Walk {
+    Continue,
+    /// Don't visit the children of the current value
+    SkipChildren,
+    /// Stop the traversal
+    Stop,
+}
+
+pub trait Visitor {
+    fn visit(&mut self, location: &Location, value: &DocumentValue) -> Walk;
+}
+
+pub trait VisitorMut {
+    /// The visitor can modify or replace the value. If the value is replaced, the children
+    /// of the new value are visited
+    fn visit_mut(&mut self, location: &Location, value: &mut DocumentValue) -> Walk;
+}
+
+impl<F> Visitor for F
+where
+    F: FnMut(&Location, &DocumentValue) -> Walk,
+{
+    fn visit(&mut self, location: &Location, value: &DocumentValue) -> Walk {
+        self(location, value)
+    }
+}
+
+impl<F> VisitorMut for F
+where
+    F: FnMut(&Location, &mut DocumentValue) -> Walk,
+{
+    fn visit_mut(&mut self, location: &Location, value: &mut DocumentValue) -> Walk {
+        self(location, value)
+    }
+}
+
+/// Segment of the [`Location`], borrowing the key from the visited value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentRef<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+impl<'a> SegmentRef<'a> {
+    pub fn to_segment(self) -> PathSegment {
+        match self {
+            SegmentRef::Key(key) => PathSegment::Key(String::from(key)),
+            SegmentRef::Index(idx) => PathSegment::Index(idx),
+        }
+    }
+}
+
+/// Location of the visited value. It only borrows the keys, the [`Path`] is built when
+/// asked for
+#[derive(Debug, Default)]
+pub struct Location<'a> {
+    segments: Vec<SegmentRef<'a>>,
+}
+
+impl<'a> Location<'a> {
+    pub fn segments(&self) -> &[SegmentRef<'a>] {
+        &self.segments
+    }
+
+    pub fn depth(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn last(&self) -> Option<SegmentRef<'a>> {
+        self.segments.last().copied()
+    }
+
+    pub fn to_path(&self) -> Path {
+        Path::from(
+            self.segments
+                .iter()
+                .map(|segment| segment.to_segment())
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+// The stack keeps the length of the parent's location and the last segment of the value's
+// location. The location is shared, and truncated to the parent when the value is popped
+struct Entry<'a, V> {
+    depth: usize,
+    segment: Option<SegmentRef<'a>>,
+    value: V,
+}
+
+impl DocumentValue {
+    /// Visits the value and all its descendants, with their locations
+    pub fn walk<V>(&self, visitor: &mut V)
+    where
+        V: Visitor + ?Sized,
+    {
+        let mut location = Location::default();
+        let mut to_walk = vec![Entry {
+            depth: 0,
+            segment: None,
+            value: self,
+        }];
+
+        while let Some(Entry {
+            depth,
+            segment,
+            value,
+        }) = to_walk.pop()
+        {
+            location.segments.truncate(depth);
+            location.segments.extend(segment);
+
+            match visitor.visit(&location, value) {
+                Walk::Continue => {}
+                Walk::SkipChildren => continue,
+                Walk::Stop => return,
+            }
+
+            let depth = location.depth();
+            match value {
+                DocumentValue::Array(array) => {
+                    to_walk.extend(array.iter().enumerate().rev().map(|(i, value)| Entry {
+                        depth,
+                        segment: Some(SegmentRef::Index(i)),
+                        value,
+                    }))
+                }
+                DocumentValue::Map(map) => {
+                    to_walk.extend(map.iter().rev().map(|(key, value)| Entry {
+                        depth,
+                        segment: Some(SegmentRef::Key(key)),
+                        value,
+                    }))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Visits the value and all its descendants, with their locations, allowing to modify them
+    pub fn walk_mut<V>(&mut self, visitor: &mut V)
+    where
+        V: VisitorMut + ?Sized,
+    {
+        let mut location = Location::default();
+        let mut to_walk = vec![Entry {
+            depth: 0,
+            segment: None,
+            value: self,
+        }];
+
+        while let Some(Entry {
+            depth,
+            segment,
+            value,
+        }) = to_walk.pop()
+        {
+            location.segments.truncate(depth);
+            location.segments.extend(segment);
+
+            match visitor.visit_mut(&location, value) {
+                Walk::Continue => {}
+                Walk::SkipChildren => continue,
+                Walk::Stop => return,
+            }
+
+            let depth = location.depth();
+            match value {
+                DocumentValue::Array(array) => {
+                    to_walk.extend(array.iter_mut().enumerate().rev().map(|(i, value)| Entry {
+                        depth,
+                        segment: Some(SegmentRef::Index(i)),
+                        value,
+                    }))
+                }
+                DocumentValue::Map(map) => {
+                    to_walk.extend(map.iter_mut().rev().map(|(key, value)| Entry {
+                        depth,
+                        segment: Some(SegmentRef::Key(key)),
+                        value,
+                    }))
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::value;
+
+    fn example() -> DocumentValue {
+        value!({
+            "a": [1, { "b": true }],
+            "c": bytes(vec![1]),
+            "secret": { "key": "value" },
+        })
+    }
+
+    #[test]
+    fn visits_in_order_with_paths() {
+        let mut visited = Vec::new();
+        example().walk(&mut |location: &Location, value: &DocumentValue| {
+            visited.push((location.to_path().to_pointer(), value.variant_name()));
+            Walk::Continue
+        });
+
+        assert_eq!(
+            vec![
+                (String::from(""), "Map"),
+                (String::from("/a"), "Array"),
+                (String::from("/a/0"), "Integer"),
+                (String::from("/a/1"), "Map"),
+                (String::from("/a/1/b"), "Bool"),
+                (String::from("/c"), "Bytes"),
+                (String::from("/secret"), "Map"),
+                (String::from("/secret/key"), "String"),
+            ],
+            visited
+        );
+    }
+
+    #[test]
+    fn skip_children_and_stop() {
+        let mut visited = Vec::new();
+        example().walk(&mut |location: &Location, _: &DocumentValue| {
+            let path = location.to_path();
+            visited.push(path.to_string());
+            match path.to_pointer().as_str() {
+                "/a" => Walk::SkipChildren,
+                "/c" => Walk::Stop,
+                _ => Walk::Continue,
+            }
+        });
+
+        assert_eq!(vec!["", "a", "c"], visited);
+    }
+
+    #[test]
+    fn redact_with_walk_mut() {
+        let mut value = example();
+        value.walk_mut(&mut |location: &Location, value: &mut DocumentValue| {
+            if location.last() == Some(SegmentRef::Key("secret")) {
+                *value = value!("<redacted>");
+                return Walk::SkipChildren;
+            }
+            Walk::Continue
+        });
+
+        assert_eq!(
+            value!({
+                "a": [1, { "b": true }],
+                "c": bytes(vec![1]),
+                "secret": "<redacted>",
+            }),
+            value
+        );
+    }
+
+    #[test]
+    fn struct_visitor() {
+        #[derive(Default)]
+        struct Statistics {
+            leaves: usize,
+            max_depth: usize,
+        }
+
+        impl Visitor for Statistics {
+            fn visit(&mut self, location: &Location, value: &DocumentValue) -> Walk {
+                if !matches!(value, DocumentValue::Array(_) | DocumentValue::Map(_)) {
+                    self.leaves += 1;
+                }
+                self.max_depth = self.max_depth.max(location.depth());
+                Walk::Continue
+            }
+        }
+
+        let mut statistics = Statistics::default();
+        example().walk(&mut statistics);
+        assert_eq!(4, statistics.leaves);
+        assert_eq!(3, statistics.max_depth);
+
+        // the visitor keeps its state between walks
+        let visitor: &mut dyn Visitor = &mut statistics;
+        value!([1, 2]).walk(visitor);
+        assert_eq!(6, statistics.leaves);
+    }
+
+    #[test]
+    fn deep_values_dont_overflow_the_stack() {
+        let mut value = DocumentValue::Null;
+        for _ in 0..100_000 {
+            value = DocumentValue::Array(vec![value]);
+        }
+
+        let mut depth = 0;
+        value.walk(&mut |location: &Location, _: &DocumentValue| {
+            depth = location.depth();
+            Walk::Continue
+        });
+        assert_eq!(100_000, depth);
+
+        // dropping deeply nested values is recursive, take the value apart level by level
+        while let DocumentValue::Array(mut array) = value {
+            value = array.pop().unwrap_or_default();
+        }
+    }
+}